@@ -0,0 +1,212 @@
+use crate::{units::Date, Error, Result};
+
+/// Easter Sunday in the Gregorian calendar (anonymous Gregorian algorithm).
+pub fn easter(year: u32) -> Date {
+	let a = year % 19;
+	let (b, c) = (year / 100, year % 100);
+	let (d, e) = (b / 4, b % 4);
+	let f = (b + 8) / 25;
+	let g = (b - f + 1) / 3;
+	let h = (19 * a + b - d - g + 15) % 30;
+	let (i, k) = (c / 4, c % 4);
+	let l = (32 + 2 * e + 2 * i - h - k) % 7;
+	let m = (a + 11 * h + 22 * l) / 451;
+	let month = (h + l - 7 * m + 114) / 31;
+	let day = (h + l - 7 * m + 114) % 31 + 1;
+	Date::new(year, month, day).expect("Easter is always in March or April")
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Holiday {
+	pub date: Date,
+	pub name: &'static str,
+}
+
+/// Holidays set by each municipality, either on a fixed day or relative to
+/// Easter (*e.g.* Ascension Thursday is 39 days after Easter).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MunicipalHoliday {
+	Fixed { month: u32, day: u32 },
+	FromEaster(i64),
+}
+
+/// Working-day calendar: national holidays, plus optional Carnival and
+/// municipal holidays, as observed by the employer.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "UncheckedCalendar"))]
+pub struct Calendar {
+	/// Carnival Tuesday isn't a mandatory holiday, but it's commonly given.
+	pub carnival: bool,
+	municipal_holidays: Vec<MunicipalHoliday>,
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct UncheckedCalendar {
+	carnival: bool,
+	municipal_holidays: Vec<MunicipalHoliday>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<UncheckedCalendar> for Calendar {
+	type Error = Error;
+
+	fn try_from(calendar: UncheckedCalendar) -> Result<Self> {
+		Self::new(calendar.carnival, calendar.municipal_holidays)
+	}
+}
+
+impl Calendar {
+	/// Fails if a fixed municipal holiday isn't a day of every year, such as
+	/// 31 April or 29 February.
+	pub fn new(carnival: bool, municipal_holidays: Vec<MunicipalHoliday>) -> Result<Self> {
+		for municipal in municipal_holidays.iter() {
+			if let MunicipalHoliday::Fixed { month, day } = *municipal {
+				// Any common year will do
+				if Date::new(2022, month, day).is_err() {
+					let message = format!("municipal holiday on {:02}-{:02}", month, day);
+					return Err(Error::InvalidContext(message));
+				}
+			}
+		}
+		Ok(Self { carnival, municipal_holidays })
+	}
+
+	pub fn municipal_holidays(&self) -> &[MunicipalHoliday] { &self.municipal_holidays }
+
+	/// Mandatory holidays (Código do Trabalho, art. 234.º). Corpus Christi,
+	/// 5 October, 1 November and 1 December were suspended from 2013 to 2015.
+	pub fn national_holidays(year: u32) -> Vec<Holiday> {
+		let easter = easter(year);
+		let fixed = |month, day, name| Holiday { date: Date::new(year, month, day).unwrap(), name };
+		let mut holidays = vec![
+			fixed(1, 1, "Ano Novo"),
+			Holiday { date: easter.add_days(-2), name: "Sexta-feira Santa" },
+			Holiday { date: easter, name: "Páscoa" },
+			fixed(4, 25, "Dia da Liberdade"),
+			fixed(5, 1, "Dia do Trabalhador"),
+			Holiday { date: easter.add_days(60), name: "Corpo de Deus" },
+			fixed(6, 10, "Dia de Portugal"),
+			fixed(8, 15, "Assunção de Nossa Senhora"),
+			fixed(10, 5, "Implantação da República"),
+			fixed(11, 1, "Dia de Todos os Santos"),
+			fixed(12, 1, "Restauração da Independência"),
+			fixed(12, 8, "Imaculada Conceição"),
+			fixed(12, 25, "Natal"),
+		];
+		if (2013..=2015).contains(&year) {
+			let suspended = [
+				"Corpo de Deus",
+				"Implantação da República",
+				"Dia de Todos os Santos",
+				"Restauração da Independência",
+			];
+			holidays.retain(|h| !suspended.contains(&h.name));
+		}
+		holidays.sort_by_key(|h| h.date);
+		holidays
+	}
+
+	/// National holidays plus the optional ones observed in this calendar.
+	pub fn holidays(&self, year: u32) -> Vec<Holiday> {
+		let mut holidays = Self::national_holidays(year);
+		if self.carnival {
+			holidays.push(Holiday { date: easter(year).add_days(-47), name: "Carnaval" });
+		}
+		for municipal in self.municipal_holidays.iter() {
+			let date = match *municipal {
+				MunicipalHoliday::Fixed { month, day } => {
+					Date::new(year, month, day).expect("checked when the calendar is built")
+				}
+				MunicipalHoliday::FromEaster(days) => easter(year).add_days(days),
+			};
+			holidays.push(Holiday { date, name: "Feriado municipal" });
+		}
+		holidays.sort_by_key(|h| h.date);
+		holidays
+	}
+
+	pub fn is_workday(&self, date: Date) -> bool {
+		!date.weekday().is_weekend() && !self.holidays(date.year()).iter().any(|h| h.date == date)
+	}
+
+	/// Workdays from `first` to `last`, both included.
+	pub fn workdays(&self, first: Date, last: Date) -> u32 {
+		let holidays: Vec<_> = (first.year()..=last.year()).flat_map(|y| self.holidays(y)).collect();
+		let is_holiday = |date: &Date| holidays.iter().any(|h| h.date == *date);
+		first.iter_through(last).filter(|d| !d.weekday().is_weekend() && !is_holiday(d)).count() as u32
+	}
+
+	/// Workdays actually worked in a year, once vacation days are taken.
+	pub fn workdays_in_year(&self, year: u32, vacation_days: u32) -> u32 {
+		let workdays = self.workdays(Date::first_of_year(year), Date::last_of_year(year));
+		workdays.saturating_sub(vacation_days)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn date(s: &str) -> Date { s.parse().unwrap() }
+
+	#[test]
+	fn easter_dates() {
+		assert_eq!(easter(2019), date("2019-04-21"));
+		assert_eq!(easter(2022), date("2022-04-17"));
+		assert_eq!(easter(2024), date("2024-03-31"));
+		assert_eq!(easter(2025), date("2025-04-20"));
+		assert_eq!(easter(2038), date("2038-04-25"));
+	}
+
+	#[test]
+	fn movable_holidays() {
+		let holidays = Calendar::national_holidays(2022);
+		assert_eq!(holidays.len(), 13);
+		assert!(holidays.contains(&Holiday { date: date("2022-04-15"), name: "Sexta-feira Santa" }));
+		assert!(holidays.contains(&Holiday { date: date("2022-06-16"), name: "Corpo de Deus" }));
+		assert_eq!(Calendar::national_holidays(2014).len(), 9);
+	}
+
+	#[test]
+	fn workdays_in_year() {
+		// https://www.dias-uteis.pt/dias-uteis_feriados_2022.htm
+		let calendar = Calendar::default();
+		assert_eq!(calendar.workdays_in_year(2022, 0), 251);
+		assert_eq!(calendar.workdays_in_year(2022, 22), 229);
+		// Carnival was on Tuesday, 1 March, and Lisbon's Santo António on Monday, 13 June
+		let lisbon = Calendar::new(true, vec![MunicipalHoliday::Fixed { month: 6, day: 13 }]).unwrap();
+		assert_eq!(lisbon.workdays_in_year(2022, 0), 249);
+		assert!(!lisbon.is_workday(date("2022-03-01")));
+		assert!(calendar.is_workday(date("2022-03-01")));
+	}
+
+	#[test]
+	fn workdays_in_range() {
+		let calendar = Calendar::default();
+		assert_eq!(calendar.workdays(date("2022-04-11"), date("2022-04-17")), 4);
+		assert_eq!(calendar.workdays(date("2022-12-26"), date("2023-01-08")), 10);
+		let ascension = Calendar::new(false, vec![MunicipalHoliday::FromEaster(39)]).unwrap();
+		assert_eq!(ascension.workdays(date("2022-05-23"), date("2022-05-29")), 4);
+	}
+
+	#[test]
+	fn invalid_municipal_holidays() {
+		for (month, day) in [(4, 31), (2, 29), (13, 1), (1, 0)] {
+			let holidays = vec![MunicipalHoliday::Fixed { month, day }];
+			assert!(matches!(Calendar::new(false, holidays), Err(Error::InvalidContext(_))));
+		}
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn deserialize_checks_municipal_holidays() {
+		let json = r#"{"carnival":true,"municipal_holidays":[{"Fixed":{"month":6,"day":13}}]}"#;
+		let lisbon: Calendar = serde_json::from_str(json).unwrap();
+		assert_eq!(lisbon.municipal_holidays(), [MunicipalHoliday::Fixed { month: 6, day: 13 }]);
+		let json = r#"{"carnival":true,"municipal_holidays":[{"Fixed":{"month":4,"day":31}}]}"#;
+		assert!(serde_json::from_str::<Calendar>(json).is_err());
+	}
+}
@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+use crate::units::{Money, TaxRate, UnitsError};
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Error, Debug)]
+pub enum Error {
+	#[error(transparent)]
+	Units(#[from] UnitsError),
+	#[error("{0} isn't a supported tax year")]
+	UnsupportedYear(u32),
+	#[error("missing table: {0}")]
+	MissingTable(String),
+	#[error("invalid family: {0}")]
+	InvalidFamily(String),
+	#[error("invalid rate for {0}: {1}")]
+	InvalidRate(&'static str, TaxRate),
+	#[error("invalid income: {0}")]
+	InvalidIncome(Money),
+	#[error("invalid context: {0}")]
+	InvalidContext(String),
+}
+
+impl From<derive_builder::UninitializedFieldError> for Error {
+	fn from(error: derive_builder::UninitializedFieldError) -> Self {
+		Self::InvalidContext(format!("{} must be set", error.field_name()))
+	}
+}
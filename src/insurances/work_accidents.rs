@@ -1,8 +1,17 @@
 use crate::{
-	salary::{Heading as SalaryHeading, Salary},
-	units::{Money, MoneyRate, TaxRate, Yearly},
+	salary::Salary,
+	units::{Money, MoneyRate, Rounding, TaxRate, Yearly},
 };
 
+/// Insured capital for a worker: the retribution they regularly receive
+/// (Lei 98/2009, art. 71.º), which includes the meal allowance. Insurers
+/// take the latter as 22 workdays over the 11 months not on vacation,
+/// whatever the actual calendar of the year, so that the capital declared
+/// on the policy doesn't change from one year to the next.
+pub fn coverage_capital(salary: &Salary) -> MoneyRate<Yearly> {
+	salary.base_salary.monthly().as_yearly() + salary.meal_allowance.daily().into_yearly(None)
+}
+
 pub struct Context {
 	pub inem_tax: TaxRate,   // 0.02
 	pub fat_tax: TaxRate,    // 0.0015
@@ -12,6 +21,7 @@ impl Default for Context {
 	fn default() -> Self { Self { inem_tax: 0.02, fat_tax: 0.0015, stamp_duty: 0.04 } }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GeneralPolicy {
 	pub premium_rate: TaxRate,
 	pub record_cost: Money,
@@ -26,16 +36,12 @@ impl GeneralPolicy {
 		self.record_cost * (1.0 + ctx.stamp_duty + ctx.inem_tax)
 	}
 
-	pub fn coverage_capital(&self, salary: &Salary) -> MoneyRate<Yearly> {
-		salary.base_salary.gross_payment() + salary.meal_allowance.gross_payment()
-	}
-
 	pub fn premium(&self, salary: &Salary) -> MoneyRate<Yearly> {
-		self.coverage_capital(salary) * self.premium_rate
+		coverage_capital(salary) * self.premium_rate
 	}
 
 	pub fn fat_cost(&self, salary: &Salary, ctx: &Context) -> MoneyRate<Yearly> {
-		self.coverage_capital(salary) * ctx.fat_tax
+		coverage_capital(salary) * ctx.fat_tax
 	}
 
 	pub fn inem_cost(&self, salary: &Salary, ctx: &Context) -> MoneyRate<Yearly> {
@@ -43,15 +49,32 @@ impl GeneralPolicy {
 	}
 
 	pub fn total_cost(&self, salary: &Salary, ctx: &Context) -> MoneyRate<Yearly> {
-		let capital = self.coverage_capital(salary);
+		let capital = coverage_capital(salary);
 		capital * (self.premium_rate + ctx.fat_tax) * (1.0 + ctx.inem_tax + ctx.stamp_duty)
 	}
+
+	/// Yearly cost of the policy for `salaries`, which the insurer rounds to
+	/// the cent on the whole policy rather than on each worker.
+	pub fn yearly_cost(&self, salaries: &[Salary], ctx: &Context) -> Money {
+		let cost: Money = salaries.iter().map(|salary| self.total_cost(salary, ctx).quantity()).sum();
+		cost.round(Rounding::HalfUp)
+	}
+
+	/// The yearly cost split into `count` installments truncated to the cent.
+	/// The first takes what's left over, and the record cost.
+	pub fn installments(&self, salaries: &[Salary], ctx: &Context, count: u32) -> Vec<Money> {
+		let (yearly, count) = (self.yearly_cost(salaries, ctx), count.max(1));
+		let installment = (yearly / count as f64).round(Rounding::Truncate);
+		let record_cost = self.record_cost(ctx).round(Rounding::HalfUp);
+		let first = yearly - installment * (count - 1) as f64 + record_cost;
+		std::iter::once(first).chain((1..count).map(|_| installment)).collect()
+	}
 }
 
 #[cfg(test)]
 mod tests {
 	use super::*;
-	use crate::salary::MealAllowance;
+	use crate::{salary::MealAllowance, tax_year::TaxYear};
 
 	fn assert_eq(yearly: MoneyRate<Yearly>, expected: f64) {
 		//println!("Yearly: {:?}\texpected: {:.02}", yearly, expected);
@@ -67,39 +90,37 @@ mod tests {
 
 	#[test]
 	fn first_three_workers() {
-		use crate::non_taxation_limits::SUBSIDIO_REFEICAO;
+		let subsidio_refeicao = TaxYear::get(2022).unwrap().non_taxation_limits.subsidio_refeicao;
 		let ctx = Context { inem_tax: 0.02, fat_tax: 0.0015, stamp_duty: 0.04 };
 		let policy = GeneralPolicy::new(0.0055, 5.0);
 		let salaries = vec![
-			Salary::new(800.0, MealAllowance::Cash(SUBSIDIO_REFEICAO), 0.0),
-			Salary::new(800.0, MealAllowance::Cash(SUBSIDIO_REFEICAO), 0.0),
+			Salary::new(800.0, MealAllowance::Cash(subsidio_refeicao), 0.0),
+			Salary::new(800.0, MealAllowance::Cash(subsidio_refeicao), 0.0),
 			Salary::new(8.85 * 52.0 * 20.0 / 12.0, MealAllowance::None, 0.0),
 		];
 		assert_eq(
-			salaries.iter().fold(MoneyRate::zero(), |acc, sal| acc + policy.coverage_capital(sal)),
+			salaries.iter().fold(MoneyRate::zero(), |acc, sal| acc + coverage_capital(sal)),
 			35446.68,
 		);
-		let total_cost =
-			salaries.iter().fold(MoneyRate::zero(), |acc, sal| acc + policy.total_cost(sal, &ctx));
-		assert_eq(total_cost, 65.69 * 4.0 + 0.25); // 0.25€ unaccounted for
-		assert_eq(
-			MoneyRate::new(policy.record_cost(&ctx), Yearly) + total_cost,
-			71.06 + 65.69 * 3.0 + 0.18, // 0.18€ unaccounted for
-		);
+		assert_eq!(policy.yearly_cost(&salaries, &ctx), Money::new(263.01));
+		// The first of the four installments invoiced
+		assert_eq!(policy.installments(&salaries, &ctx, 4)[0], Money::new(71.06));
 	}
 
 	#[test]
+	#[allow(clippy::useless_vec)]
 	fn four_workers() {
 		let ctx = Context { inem_tax: 0.02, fat_tax: 0.0015, stamp_duty: 0.04 };
 		let pol = GeneralPolicy::new(0.0055, 5.0);
+		let tax_year = TaxYear::get(2022).unwrap();
 		let salaries = vec![
-			Salary::new(800.0, MealAllowance::cash(), 0.0),
-			Salary::new(800.0, MealAllowance::cash(), 0.0),
-			Salary::new(1270.0, MealAllowance::card(), 0.0),
-			Salary::new(2364.0, MealAllowance::card(), 0.0),
+			Salary::new(800.0, MealAllowance::cash(tax_year), 0.0),
+			Salary::new(800.0, MealAllowance::cash(tax_year), 0.0),
+			Salary::new(1270.0, MealAllowance::card(tax_year), 0.0),
+			Salary::new(2364.0, MealAllowance::card(tax_year), 0.0),
 		];
 		assert_eq(
-			salaries.iter().fold(MoneyRate::zero(), |acc, sal| acc + pol.coverage_capital(sal)),
+			salaries.iter().fold(MoneyRate::zero(), |acc, sal| acc + coverage_capital(sal)),
 			79277.60,
 		);
 		assert_eq(
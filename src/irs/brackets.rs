@@ -1,38 +1,115 @@
-use crate::units::{Money, MoneyRate, TaxRate, Yearly};
+use std::fmt::Display;
 
-fn positive_difference(a: f64, b: f64) -> f64 { (a - b).max(0.0) }
+use crate::{
+	units::{Locale, Money, MoneyRate, Rounding, TaxRate, Yearly},
+	Error, Result,
+};
 
-pub fn taxes(income: MoneyRate<Yearly>, brackets: &[Bracket]) -> MoneyRate<Yearly> {
-	let value = positive_difference(income.quantity().value(), 4104.0);
+/// Tax due on a taxable income (rendimento coletável), before any deductions
+/// from the tax.
+pub fn taxes(taxable_income: MoneyRate<Yearly>, brackets: &[Bracket]) -> MoneyRate<Yearly> {
+	let value = taxable_income.quantity().value().max(0.0);
 	let mut taxed = 0.0;
 	let mut prev_bracket_value = 0.0;
 	for bracket in brackets.iter() {
-		if bracket.rate.quantity().value() > value {
-			taxed += bracket.tax * (value - prev_bracket_value);
-			break;
-		} else {
-			taxed += bracket.tax * (bracket.rate.quantity().value() - prev_bracket_value);
+		match bracket.limit() {
+			Some(limit) if limit.value() <= value => {
+				taxed += bracket.tax * (limit.value() - prev_bracket_value);
+				prev_bracket_value = limit.value();
+			}
+			_ => {
+				taxed += bracket.tax * (value - prev_bracket_value);
+				break;
+			}
 		}
-		prev_bracket_value = bracket.rate.quantity().value();
 	}
-	MoneyRate::new(taxed, Yearly)
+	MoneyRate::new(Money::new(taxed).round(Rounding::HalfUp), Yearly)
 }
 
+/// Taxa adicional de solidariedade on a taxable income: 2.5% of the part
+/// between 80 000€ and 250 000€, and 5% of the part above (CIRS, art. 68.º-A).
+pub fn solidarity_surcharge(taxable_income: MoneyRate<Yearly>) -> MoneyRate<Yearly> {
+	const SURCHARGES: [(f64, TaxRate); 2] = [(80_000.0, 0.025), (250_000.0, 0.05)];
+	let value = taxable_income.quantity().value();
+	let taxed = SURCHARGES.iter().enumerate().fold(0.0, |taxed, (i, &(lower, tax))| {
+		let upper = SURCHARGES.get(i + 1).map_or(value, |&(next, _)| next);
+		taxed + tax * (value.min(upper) - lower).max(0.0)
+	});
+	MoneyRate::new(Money::new(taxed).round(Rounding::HalfUp), Yearly)
+}
+
+#[derive(Debug, Clone, Copy)]
 pub struct Bracket {
-	rate: MoneyRate<Yearly>,
+	/// Upper limit of the bracket, or `None` for the open top bracket.
+	rate: Option<MoneyRate<Yearly>>,
 	tax: TaxRate,
 }
 
 impl Bracket {
 	const fn new(yearly: f64, tax: TaxRate) -> Self {
-		Self { rate: MoneyRate::new_const(Money::new(yearly), Yearly), tax }
+		Self { rate: Some(MoneyRate::new_const(Money::new(yearly), Yearly)), tax }
 	}
 
-	pub fn rate(self) -> MoneyRate<Yearly> { self.rate }
+	/// The top bracket, with no upper limit.
+	const fn top(tax: TaxRate) -> Self { Self { rate: None, tax } }
+
+	/// Taxable income up to which the bracket applies, or `None` for the top
+	/// bracket.
+	pub fn rate(&self) -> Option<MoneyRate<Yearly>> { self.rate }
 
-	pub fn tax(self) -> TaxRate { self.tax }
+	fn limit(&self) -> Option<Money> { self.rate.map(|rate| rate.quantity()) }
+
+	pub fn tax(&self) -> TaxRate { self.tax }
 }
 
+pub const year_2026: [Bracket; 9] = [
+	Bracket::new(8_342.00, 0.125),
+	Bracket::new(12_587.00, 0.157),
+	Bracket::new(17_838.00, 0.212),
+	Bracket::new(23_089.00, 0.241),
+	Bracket::new(29_397.00, 0.311),
+	Bracket::new(43_090.00, 0.349),
+	Bracket::new(46_566.00, 0.431),
+	Bracket::new(86_634.00, 0.446),
+	Bracket::top(0.48),
+];
+
+pub const year_2025: [Bracket; 9] = [
+	Bracket::new(8_059.00, 0.125),
+	Bracket::new(12_160.00, 0.160),
+	Bracket::new(17_233.00, 0.215),
+	Bracket::new(22_306.00, 0.244),
+	Bracket::new(28_400.00, 0.314),
+	Bracket::new(41_629.00, 0.349),
+	Bracket::new(44_987.00, 0.431),
+	Bracket::new(83_696.00, 0.446),
+	Bracket::top(0.48),
+];
+
+pub const year_2024: [Bracket; 9] = [
+	Bracket::new(7_703.00, 0.130),
+	Bracket::new(11_623.00, 0.165),
+	Bracket::new(16_472.00, 0.220),
+	Bracket::new(21_321.00, 0.250),
+	Bracket::new(27_146.00, 0.320),
+	Bracket::new(39_791.00, 0.355),
+	Bracket::new(51_997.00, 0.435),
+	Bracket::new(81_199.00, 0.450),
+	Bracket::top(0.48),
+];
+
+pub const year_2023: [Bracket; 9] = [
+	Bracket::new(7_479.00, 0.145),
+	Bracket::new(11_284.00, 0.210),
+	Bracket::new(15_992.00, 0.265),
+	Bracket::new(20_700.00, 0.285),
+	Bracket::new(26_355.00, 0.350),
+	Bracket::new(38_632.00, 0.370),
+	Bracket::new(50_483.00, 0.435),
+	Bracket::new(78_834.00, 0.450),
+	Bracket::top(0.48),
+];
+
 pub const year_2022: [Bracket; 9] = [
 	Bracket::new(7_116.00, 0.145),
 	Bracket::new(10_736.00, 0.230),
@@ -42,7 +119,7 @@ pub const year_2022: [Bracket; 9] = [
 	Bracket::new(36_757.00, 0.370),
 	Bracket::new(48_033.00, 0.435),
 	Bracket::new(75_009.00, 0.450),
-	Bracket::new(std::f64::INFINITY, 0.48),
+	Bracket::top(0.48),
 ];
 
 pub const year_2021: [Bracket; 7] = [
@@ -52,7 +129,7 @@ pub const year_2021: [Bracket; 7] = [
 	Bracket::new(25_075.00, 0.350),
 	Bracket::new(36_967.00, 0.370),
 	Bracket::new(80_882.00, 0.450),
-	Bracket::new(std::f64::INFINITY, 0.48),
+	Bracket::top(0.48),
 ];
 
 pub const year_2019: [Bracket; 7] = [
@@ -62,23 +139,139 @@ pub const year_2019: [Bracket; 7] = [
 	Bracket::new(25_000.00, 0.350),
 	Bracket::new(36_856.00, 0.370),
 	Bracket::new(80_640.00, 0.450),
-	Bracket::new(std::f64::INFINITY, 0.48),
+	Bracket::top(0.48),
 ];
 
-pub fn escalao(rendimento_colectavel: f64) -> (usize, (MoneyRate<Yearly>, f64, f64)) {
-	(year_2022.iter())
-		.scan((0.0, 0.0, 0.001), |(lim, tax, a_abater), Bracket { rate: new_lim, tax: new_tax }| {
-			if rendimento_colectavel < *lim {
-				//println!("{}º escalao (<{:.2}): {:.2} * {:.2}% - {:.2}", i + 1, lim,
-				// rend_coletavel, tax * 100.0, a_abater);
-				return None;
+/// Where a taxable income falls in a bracket table.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BracketPosition {
+	/// Index of the bracket, from 0.
+	pub index: usize,
+	/// Rate on the last euro of income.
+	pub marginal_rate: TaxRate,
+	/// Tax over the taxable income.
+	pub average_rate: TaxRate,
+	/// The tax is the income at the marginal rate minus the parcela a abater.
+	pub parcela_a_abater: Money,
+	pub tax: Money,
+}
+
+impl BracketPosition {
+	pub fn new(taxable_income: MoneyRate<Yearly>, brackets: &[Bracket]) -> Result<Self> {
+		let income = taxable_income.quantity();
+		if income < Money::zero() {
+			return Err(Error::InvalidIncome(income));
+		}
+		let index = (brackets.iter().position(|bracket| bracket.limit().is_none_or(|limit| income <= limit)))
+			.ok_or_else(|| Error::MissingTable(format!("bracket for a taxable income of {}", income)))?;
+		let parcela_a_abater = parcela_a_abater(brackets, index);
+		let marginal_rate = brackets[index].tax;
+		let tax = (income * marginal_rate - parcela_a_abater).round(Rounding::HalfUp);
+		let average_rate = if income == Money::zero() { 0.0 } else { tax.value() / income.value() };
+		Ok(Self { index, marginal_rate, average_rate, parcela_a_abater, tax })
+	}
+}
+
+/// What taxing the whole income at the rate of bracket `index` adds to the
+/// tax of the lower brackets.
+fn parcela_a_abater(brackets: &[Bracket], index: usize) -> Money {
+	(brackets.windows(2).take(index))
+		.filter_map(|pair| Some(pair[0].limit()? * (pair[1].tax - pair[0].tax)))
+		.sum()
+}
+
+/// A bracket table laid out as in the Código do IRS, with the average rate at
+/// the top of each bracket and its parcela a abater.
+#[derive(Debug, Clone, Copy)]
+pub struct BracketTable<'a> {
+	pub brackets: &'a [Bracket],
+	pub locale: Locale,
+}
+
+impl Display for BracketTable<'_> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let percent = |rate: TaxRate| {
+			let percent = format!("{:.3}%", rate * 100.0);
+			if self.locale == Locale::PtPt {
+				percent.replace('.', ",")
+			} else {
+				percent
 			}
-			*a_abater += *lim * new_tax - *lim * *tax;
-			*lim = new_lim.quantity().value();
-			*tax = *new_tax;
-			Some((*new_lim, *new_tax, *a_abater))
-		})
-		.enumerate()
-		.last()
-		.unwrap()
+		};
+		let money = |money: Money| format!("{:.0}", money.localized(self.locale));
+		writeln!(
+			f,
+			"{:<40} {:>12} {:>12} {:>18}",
+			"Rendimento coletável", "Taxa normal", "Taxa média", "Parcela a abater"
+		)?;
+		let mut lower = None;
+		for (index, bracket) in self.brackets.iter().enumerate() {
+			let income = match (lower, bracket.limit()) {
+				(None, Some(upper)) => format!("Até {}", money(upper)),
+				(Some(lower), Some(upper)) => format!("De mais de {} até {}", money(lower), money(upper)),
+				(Some(lower), None) => format!("Superior a {}", money(lower)),
+				(None, None) => "Qualquer".to_string(),
+			};
+			let average = match bracket.rate {
+				None => "-".to_string(),
+				Some(upper) => percent(
+					BracketPosition::new(upper, self.brackets).map_err(|_| std::fmt::Error)?.average_rate,
+				),
+			};
+			let parcela = parcela_a_abater(self.brackets, index).localized(self.locale);
+			writeln!(f, "{:<40} {:>12} {:>12} {:>18.2}", income, percent(bracket.tax), average, parcela)?;
+			lower = bracket.limit();
+		}
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tax_year::TaxYear;
+
+	#[test]
+	fn position_matches_taxes() {
+		for brackets in
+			[&year_2019[..], &year_2021, &year_2022, &year_2023, &year_2024, &year_2025, &year_2026]
+		{
+			for cents in (0..20_000_000).step_by(99_991) {
+				let income = MoneyRate::new(Money::from_cents(cents), Yearly);
+				let position = BracketPosition::new(income, brackets).unwrap();
+				assert_eq!(position.tax, taxes(income, brackets).quantity(), "{}", income);
+			}
+		}
+		let position = BracketPosition::new(MoneyRate::new(9896.0, Yearly), &year_2022).unwrap();
+		assert_eq!(position.index, 1);
+		assert_eq!(position.marginal_rate, 0.23);
+		assert_eq!(position.parcela_a_abater, Money::new(604.86));
+		assert!((position.average_rate - 0.168878).abs() < 0.000001);
+		// At the limit, still in the lower bracket
+		let position = BracketPosition::new(MoneyRate::new(7116.0, Yearly), &year_2022).unwrap();
+		assert_eq!(position.index, 0);
+		assert!(matches!(
+			BracketPosition::new(MoneyRate::new(-1.0, Yearly), &year_2022),
+			Err(Error::InvalidIncome(_))
+		));
+		assert!(matches!(
+			BracketPosition::new(MoneyRate::new(1.0, Yearly), &[]),
+			Err(Error::MissingTable(_))
+		));
+		let position = BracketPosition::new(MoneyRate::new(Money::MAX, Yearly), &year_2022).unwrap();
+		assert_eq!(position.index, 8);
+		assert_eq!(taxes(MoneyRate::new(Money::MAX, Yearly), &year_2022).quantity(), position.tax);
+	}
+
+	#[test]
+	fn table() {
+		let table = TaxYear::get(2022).unwrap().bracket_table(Locale::PtPt).to_string();
+		let lines: Vec<_> = table.lines().collect();
+		assert_eq!(lines.len(), 10);
+		assert!(lines[1].starts_with("Até 7.116 €"));
+		assert!(lines[2].starts_with("De mais de 7.116 € até 10.736 €"));
+		assert!(lines[2].contains("23,000%"));
+		assert!(lines[2].ends_with("604,86 €"));
+		assert!(lines[9].starts_with("Superior a 75.009 €"));
+	}
 }
@@ -0,0 +1,359 @@
+//! Category B income (recibos verdes) under the simplified regime (CIRS, art.
+//! 31.º).
+
+use crate::{
+	irs::EstatutoFiscal,
+	salary::Context,
+	tax_year::TaxYear,
+	units::{Date, Money, Rounding, TaxRate},
+	Error, Result,
+};
+
+/// Kinds of category B income, each with the coefficient of it that's taxed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Activity {
+	/// Sales of goods, and restaurant and hotel services.
+	Sales,
+	/// Professions listed in the table of art. 151.º.
+	ListedProfession,
+	/// Services not in the table of art. 151.º.
+	OtherServices,
+	/// Intellectual property, other than by its authors.
+	IntellectualProperty,
+	/// Alojamento local.
+	LocalLodging,
+	/// Alojamento local in a house or apartment in an área de contenção.
+	LocalLodgingContainment,
+	/// Subsidies to the activity.
+	ExploitationSubsidies,
+	/// Other subsidies.
+	OtherSubsidies,
+}
+
+impl Activity {
+	pub fn coefficient(&self) -> f64 {
+		match self {
+			Self::Sales => 0.15,
+			Self::ListedProfession => 0.75,
+			Self::OtherServices => 0.35,
+			Self::IntellectualProperty => 0.95,
+			Self::LocalLodging => 0.35,
+			Self::LocalLodgingContainment => 0.50,
+			Self::ExploitationSubsidies => 0.10,
+			Self::OtherSubsidies => 0.30,
+		}
+	}
+
+	/// Rate the client withholds, if any (CIRS, art. 101.º).
+	pub fn withholding_rate(&self, ctx: &Context) -> Result<Option<TaxRate>> {
+		let high_value = matches!(
+			ctx.estatuto_fiscal,
+			EstatutoFiscal::ResidenteNãoHabitual { elevado_valor_acrescentado: true }
+		);
+		Ok(match self {
+			Self::ListedProfession if high_value => Some(0.20),
+			Self::ListedProfession => Some(ctx.tax_year()?.cat_b_withholding),
+			Self::OtherServices => Some(0.115),
+			Self::IntellectualProperty => Some(0.165),
+			_ => None,
+		})
+	}
+
+	/// Services whose untaxed share must be backed by expenses (n.º 13), and
+	/// whose coefficient is reduced when starting the activity (n.º 10).
+	fn is_service(&self) -> bool { matches!(self, Self::ListedProfession | Self::OtherServices) }
+}
+
+/// Gross income from an activity over the year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receipts {
+	pub activity: Activity,
+	pub gross: Money,
+}
+
+/// A recibo verde issued in the year.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Invoice {
+	pub date: Date,
+	pub activity: Activity,
+	pub gross: Money,
+	/// Only clients with organised accounts withhold.
+	pub client_withholds: bool,
+}
+
+/// IRS withheld on a year of invoices, whose total is compared with the tax
+/// due in the settlement's [`Income::withheld`](super::settlement::Income::withheld).
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct YearlyWithholding {
+	/// Withheld on each invoice, in the order given.
+	pub invoices: Vec<Money>,
+	/// Day of the invoice from which the exemption no longer applies, if it
+	/// applied at all.
+	pub exemption_lost_on: Option<Date>,
+}
+
+impl YearlyWithholding {
+	pub fn total(&self) -> Money { self.invoices.iter().copied().sum() }
+}
+
+/// Withholding on the invoices of `ctx.year`. It's waived while the year's
+/// invoices don't exceed the threshold of the tax year, if the previous year's
+/// category B income didn't exceed that year's threshold, or this year's when
+/// the previous year isn't supported. The invoice that crosses it, and all
+/// after it, are withheld (CIRS, art. 101.º-B).
+pub fn withholding(
+	invoices: &[Invoice], previous_year_income: Money, ctx: &Context,
+) -> Result<YearlyWithholding> {
+	if let Some(invoice) = invoices.iter().find(|invoice| invoice.date.year() != ctx.year) {
+		return Err(Error::InvalidContext(format!("invoice of {} in {}", invoice.date, ctx.year)));
+	}
+	let tax_year = ctx.tax_year()?;
+	let previous_year = match TaxYear::get(ctx.year - 1) {
+		Err(Error::UnsupportedYear(_)) => tax_year,
+		previous_year => previous_year?,
+	};
+	let threshold = tax_year.non_taxation_limits.isencao_retencao_cat_b.quantity();
+	let mut exempt =
+		previous_year_income <= previous_year.non_taxation_limits.isencao_retencao_cat_b.quantity();
+	let mut order: Vec<_> = (0..invoices.len()).collect();
+	order.sort_by_key(|&i| invoices[i].date);
+	let (mut invoiced, mut exemption_lost_on) = (Money::zero(), None);
+	let mut withheld = vec![Money::zero(); invoices.len()];
+	for i in order {
+		let invoice = &invoices[i];
+		invoiced += invoice.gross;
+		if exempt && invoiced > threshold {
+			exempt = false;
+			exemption_lost_on = Some(invoice.date);
+		}
+		let rate = invoice.activity.withholding_rate(ctx)?;
+		if let (Some(rate), true, false) = (rate, invoice.client_withholds, exempt) {
+			withheld[i] = (invoice.gross * rate).round(Rounding::Truncate);
+		}
+	}
+	Ok(YearlyWithholding { invoices: withheld, exemption_lost_on })
+}
+
+/// A self-employed worker's year.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SelfEmployment {
+	pub receipts: Vec<Receipts>,
+	/// Expenses backing the untaxed share of services: staff, rents, e-fatura
+	/// expenses of the activity, and the like (n.º 14).
+	pub expenses: Money,
+	/// Year the activity started, if it's one of its first two.
+	pub start_year: Option<u32>,
+}
+
+/// Category B income under the simplified regime.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimplifiedIncome {
+	pub gross: Money,
+	/// Gross income times the coefficients of its activities.
+	pub base: Money,
+	/// Added back when the expenses don't reach 15% of the gross income from
+	/// services.
+	pub unjustified_expenses: Money,
+}
+
+impl SimplifiedIncome {
+	/// Rendimento tributável, which is added to the taxable income.
+	pub fn taxable(&self) -> Money { self.base + self.unjustified_expenses }
+}
+
+impl SelfEmployment {
+	/// Coefficients of services are halved in the first year of activity, and
+	/// reduced by a quarter in the second.
+	fn start_reduction(&self, year: u32) -> f64 {
+		match self.start_year.map(|start| year.checked_sub(start)) {
+			Some(Some(0)) => 0.5,
+			Some(Some(1)) => 0.75,
+			_ => 1.0,
+		}
+	}
+
+	/// Income of the year, for a worker with `cat_a` gross category A income
+	/// and `ss_contributions` in compulsory contributions.
+	pub fn income(
+		&self, cat_a: Money, ss_contributions: Money, tax_year: &TaxYear,
+	) -> Result<SimplifiedIncome> {
+		if let Some(receipts) = self.receipts.iter().find(|receipts| receipts.gross < Money::zero()) {
+			return Err(Error::InvalidIncome(receipts.gross));
+		}
+		if self.start_year.is_some_and(|start| start > tax_year.year) {
+			return Err(Error::InvalidContext(format!("activity starting after {}", tax_year.year)));
+		}
+		let reduction = self.start_reduction(tax_year.year);
+		let coefficient = |activity: Activity| match activity.is_service() {
+			true => activity.coefficient() * reduction,
+			false => activity.coefficient(),
+		};
+		let gross = self.receipts.iter().map(|receipts| receipts.gross).sum();
+		let base = (self.receipts.iter())
+			.map(|receipts| (receipts.gross * coefficient(receipts.activity)).round(Rounding::HalfUp))
+			.sum();
+		let services: Money = self
+			.receipts
+			.iter()
+			.filter(|receipts| receipts.activity.is_service())
+			.map(|receipts| receipts.gross)
+			.sum();
+		// The dedução específica, or the contributions if higher, also count as
+		// justified, unless already deducted from category A income
+		let deduction = match cat_a > Money::zero() {
+			true => Money::zero(),
+			false => tax_year.specific_deduction.max(ss_contributions),
+		};
+		let to_justify = (services * 0.15).round(Rounding::HalfUp);
+		let unjustified_expenses = match services > Money::zero() {
+			true => (to_justify - deduction - self.expenses).max(Money::zero()),
+			false => Money::zero(),
+		};
+		Ok(SimplifiedIncome { gross, base, unjustified_expenses })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::salary::ContextBuilder;
+
+	// Income of a worker without category A income nor contributions
+	fn cat_b_only(activity: &SelfEmployment, tax_year: &TaxYear) -> Result<SimplifiedIncome> {
+		activity.income(Money::zero(), Money::zero(), tax_year)
+	}
+
+	fn services(gross: f64) -> SelfEmployment {
+		let receipts = vec![Receipts { activity: Activity::ListedProfession, gross: Money::new(gross) }];
+		SelfEmployment { receipts, ..Default::default() }
+	}
+
+	#[test]
+	fn coefficients() {
+		let tax_year = TaxYear::get(2022).unwrap();
+		let income = cat_b_only(&services(20_000.0), tax_year).unwrap();
+		assert_eq!(income.base, Money::new(15_000.0));
+		assert_eq!(income.unjustified_expenses, Money::zero());
+		let receipts = vec![
+			Receipts { activity: Activity::Sales, gross: Money::new(10_000.0) },
+			Receipts { activity: Activity::OtherServices, gross: Money::new(10_000.0) },
+		];
+		let income = cat_b_only(&SelfEmployment { receipts, ..Default::default() }, tax_year).unwrap();
+		assert_eq!(income.base, Money::new(1500.0 + 3500.0));
+		assert_eq!(income.gross, Money::new(20_000.0));
+	}
+
+	#[test]
+	fn unjustified_expenses() {
+		let tax_year = TaxYear::get(2022).unwrap();
+		// 15% of 50000€ is 7500€, of which 4104€ are deducted and 1000€ justified
+		let activity = SelfEmployment { expenses: Money::new(1000.0), ..services(50_000.0) };
+		let income = cat_b_only(&activity, tax_year).unwrap();
+		assert_eq!(income.unjustified_expenses, Money::new(2396.0));
+		assert_eq!(income.taxable(), Money::new(37_500.0 + 2396.0));
+		// Or the contributions, when higher than 4104€
+		let income = activity.income(Money::zero(), Money::new(5000.0), tax_year).unwrap();
+		assert_eq!(income.unjustified_expenses, Money::new(1500.0));
+		// Nothing is deducted if it was from category A income
+		let income = activity.income(Money::new(10_000.0), Money::zero(), tax_year).unwrap();
+		assert_eq!(income.unjustified_expenses, Money::new(6500.0));
+	}
+
+	#[test]
+	fn start_of_activity() {
+		let tax_year = TaxYear::get(2022).unwrap();
+		let first = SelfEmployment { start_year: Some(2022), ..services(20_000.0) };
+		assert_eq!(cat_b_only(&first, tax_year).unwrap().base, Money::new(7500.0));
+		let second = SelfEmployment { start_year: Some(2021), ..services(20_000.0) };
+		assert_eq!(cat_b_only(&second, tax_year).unwrap().base, Money::new(11_250.0));
+		let third = SelfEmployment { start_year: Some(2020), ..services(20_000.0) };
+		assert_eq!(cat_b_only(&third, tax_year).unwrap().base, Money::new(15_000.0));
+		let future = SelfEmployment { start_year: Some(2023), ..services(20_000.0) };
+		assert!(matches!(cat_b_only(&future, tax_year), Err(Error::InvalidContext(_))));
+	}
+
+	fn invoice(date: &str, gross: f64) -> Invoice {
+		let date = date.parse().unwrap();
+		Invoice {
+			date,
+			activity: Activity::ListedProfession,
+			gross: Money::new(gross),
+			client_withholds: true,
+		}
+	}
+
+	#[test]
+	fn withholding_rates() {
+		let ctx = Context::default();
+		let invoices = [
+			invoice("2022-01-31", 1000.0),
+			Invoice { activity: Activity::OtherServices, ..invoice("2022-02-28", 1000.0) },
+			Invoice { activity: Activity::IntellectualProperty, ..invoice("2022-03-31", 1000.0) },
+			Invoice { activity: Activity::Sales, ..invoice("2022-04-30", 1000.0) },
+			Invoice { client_withholds: false, ..invoice("2022-05-31", 1000.0) },
+		];
+		let withholding = withholding(&invoices, Money::new(20_000.0), &ctx).unwrap();
+		assert_eq!(withholding.invoices, [250.0, 115.0, 165.0, 0.0, 0.0].map(Money::new));
+		assert_eq!(withholding.total(), Money::new(530.0));
+		assert_eq!(withholding.exemption_lost_on, None);
+		// 23% from 2025, and 20% in high-value activities
+		let ctx = ContextBuilder::default().year(2025).build().unwrap();
+		let invoices = [invoice("2025-01-31", 1000.0)];
+		assert_eq!(
+			super::withholding(&invoices, Money::new(20_000.0), &ctx).unwrap().total(),
+			Money::new(230.0)
+		);
+		let estatuto_fiscal = EstatutoFiscal::ResidenteNãoHabitual { elevado_valor_acrescentado: true };
+		let ctx = ContextBuilder::default().year(2025).estatuto_fiscal(estatuto_fiscal).build().unwrap();
+		assert_eq!(
+			super::withholding(&invoices, Money::new(20_000.0), &ctx).unwrap().total(),
+			Money::new(200.0)
+		);
+	}
+
+	#[test]
+	fn withholding_exemption() {
+		let ctx = Context::default();
+		let invoices: Vec<_> =
+			(1..=12).map(|month| invoice(&format!("2022-{:02}-28", month), 800.0)).collect();
+		// Under the threshold the year before, and all year
+		let withheld = withholding(&invoices, Money::new(9000.0), &ctx).unwrap();
+		assert_eq!(withheld.total(), Money::zero());
+		// Exempt until the invoice that crosses 10000€
+		let invoices: Vec<_> =
+			(1..=12).map(|month| invoice(&format!("2022-{:02}-28", month), 1500.0)).collect();
+		let withheld = withholding(&invoices, Money::new(9000.0), &ctx).unwrap();
+		assert_eq!(withheld.exemption_lost_on, Some("2022-07-28".parse().unwrap()));
+		assert_eq!(withheld.invoices[5], Money::zero());
+		assert_eq!(withheld.total(), Money::new(6.0 * 375.0));
+		// Over the threshold the year before
+		let withheld = withholding(&invoices, Money::new(10_001.0), &ctx).unwrap();
+		assert_eq!(withheld.total(), Money::new(12.0 * 375.0));
+		let invoices = [invoice("2021-12-31", 1000.0)];
+		assert!(matches!(withholding(&invoices, Money::zero(), &ctx), Err(Error::InvalidContext(_))));
+	}
+
+	#[test]
+	fn withholding_exemption_of_the_previous_year() {
+		let exempt = |year: u32, previous_year_income: f64| {
+			let ctx = ContextBuilder::default().year(year).build().unwrap();
+			let invoices = [invoice(&format!("{}-01-31", year), 1000.0)];
+			withholding(&invoices, Money::new(previous_year_income), &ctx).unwrap().total() == Money::zero()
+		};
+		// 10000€ in 2023, 12500€ in 2024 and 15000€ in 2025
+		assert!(!exempt(2024, 11_000.0));
+		assert!(exempt(2024, 10_000.0));
+		assert!(!exempt(2025, 13_000.0));
+		assert!(exempt(2025, 12_500.0));
+		assert!(exempt(2026, 15_000.0));
+		// 2018 isn't supported, so 2019's threshold stands in
+		assert!(exempt(2019, 10_000.0));
+		assert!(!exempt(2019, 10_001.0));
+	}
+}
@@ -0,0 +1,171 @@
+//! IRS Jovem: partial exemption of the income of young workers in their first
+//! years of activity (CIRS, art. 12.º-B).
+
+use crate::{
+	tax_year::TaxYear,
+	units::{Money, Rounding, TaxRate},
+};
+
+/// A worker who may benefit from IRS Jovem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct YoungWorker {
+	/// Age on the last day of the tax year.
+	pub age: u32,
+	/// First year with work income after finishing studies.
+	pub first_year: u32,
+	/// Doctorates have a higher age limit until 2024.
+	pub doctorate: bool,
+}
+
+impl YoungWorker {
+	/// Exempt part of category A `income`, for the year of activity in
+	/// `tax_year`.
+	pub fn exemption(&self, income: Money, tax_year: &TaxYear) -> Money {
+		self.exemptions(income, Money::zero(), tax_year).0
+	}
+
+	/// Exempt parts of category A and category B income, which share the
+	/// year's cap in proportion to each. Category B income is only covered
+	/// from 2022.
+	pub fn exemptions(&self, cat_a: Money, cat_b: Money, tax_year: &TaxYear) -> (Money, Money) {
+		let none = (Money::zero(), Money::zero());
+		let Some(rules) = tax_year.irs_jovem else { return none };
+		let max_age = if self.doctorate { rules.max_age_doctorate } else { rules.max_age };
+		if self.age > max_age || self.first_year > tax_year.year {
+			return none;
+		}
+		let Some(&(share, cap)) = rules.exemptions.get((tax_year.year - self.first_year) as usize) else {
+			return none;
+		};
+		let cat_a = cat_a.max(Money::zero());
+		let cat_b = if rules.cat_b { cat_b.max(Money::zero()) } else { Money::zero() };
+		let income = cat_a + cat_b;
+		if income == Money::zero() {
+			return none;
+		}
+		let exempt = (income * share).min(tax_year.ias.quantity() * cap);
+		let cat_b_exempt = (exempt * (cat_b.value() / income.value())).round(Rounding::HalfUp);
+		(exempt - cat_b_exempt, cat_b_exempt)
+	}
+}
+
+/// IRS Jovem rules in force in a year.
+#[derive(Debug)]
+pub struct Rules {
+	pub max_age: u32,
+	pub max_age_doctorate: u32,
+	/// Exempt share of the income, and its cap in IAS, by year of activity.
+	pub exemptions: &'static [(TaxRate, f64)],
+	/// Whether category B income is covered too.
+	pub cat_b: bool,
+	/// Whether the exemption is taken into account when withholding.
+	pub withholding: bool,
+}
+
+pub const year_2020: Rules = Rules {
+	max_age: 26,
+	max_age_doctorate: 30,
+	exemptions: &[(0.3, 7.5), (0.2, 5.0), (0.1, 2.5)],
+	cat_b: false,
+	withholding: false,
+};
+
+pub const year_2022: Rules = Rules {
+	max_age: 26,
+	max_age_doctorate: 30,
+	exemptions: &[(0.3, 7.5), (0.3, 7.5), (0.2, 5.0), (0.2, 5.0), (0.1, 2.5)],
+	cat_b: true,
+	withholding: false,
+};
+
+pub const year_2023: Rules = Rules {
+	max_age: 26,
+	max_age_doctorate: 30,
+	exemptions: &[(0.5, 12.5), (0.4, 10.0), (0.3, 7.5), (0.3, 7.5), (0.2, 5.0)],
+	cat_b: true,
+	withholding: true,
+};
+
+pub const year_2024: Rules = Rules {
+	max_age: 26,
+	max_age_doctorate: 30,
+	exemptions: &[(1.0, 40.0), (0.75, 30.0), (0.5, 20.0), (0.5, 20.0), (0.25, 10.0)],
+	cat_b: true,
+	withholding: true,
+};
+
+/// From 2025, any worker up to 35 benefits for 10 years, whatever their
+/// qualifications, up to 55 IAS a year.
+pub const year_2025: Rules = Rules {
+	max_age: 35,
+	max_age_doctorate: 35,
+	exemptions: &[
+		(1.0, 55.0),
+		(0.75, 55.0),
+		(0.75, 55.0),
+		(0.75, 55.0),
+		(0.5, 55.0),
+		(0.5, 55.0),
+		(0.5, 55.0),
+		(0.25, 55.0),
+		(0.25, 55.0),
+		(0.25, 55.0),
+	],
+	cat_b: true,
+	withholding: true,
+};
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::salary::{Context, ContextBuilder, MealAllowance, Salary};
+
+	#[test]
+	fn exemption() {
+		let worker = YoungWorker { age: 25, first_year: 2021, doctorate: false };
+		let tax_year = TaxYear::get(2022).unwrap();
+		// Second year: 30%, up to 7.5 IAS
+		assert_eq!(worker.exemption(Money::new(10_000.0), tax_year), Money::new(3000.0));
+		assert_eq!(worker.exemption(Money::new(20_000.0), tax_year), Money::new(3324.0));
+		// Too old, unless holding a doctorate
+		let older = YoungWorker { age: 28, ..worker };
+		assert_eq!(older.exemption(Money::new(10_000.0), tax_year), Money::zero());
+		let doctorate = YoungWorker { doctorate: true, ..older };
+		assert_eq!(doctorate.exemption(Money::new(10_000.0), tax_year), Money::new(3000.0));
+		// Not before it existed, nor after the years of activity it covers
+		assert_eq!(worker.exemption(Money::new(10_000.0), TaxYear::get(2019).unwrap()), Money::zero());
+		let veteran = YoungWorker { first_year: 2016, ..worker };
+		assert_eq!(veteran.exemption(Money::new(10_000.0), tax_year), Money::zero());
+		// The 2025 redesign covers older workers for longer
+		let worker = YoungWorker { age: 33, first_year: 2019, doctorate: false };
+		let tax_year = TaxYear::get(2025).unwrap();
+		assert_eq!(worker.exemption(Money::new(20_000.0), tax_year), Money::new(10_000.0));
+	}
+
+	#[test]
+	fn net_pay() {
+		let salary = Salary::new(1500.0, MealAllowance::None, 0.0);
+		let worker = YoungWorker { age: 24, first_year: 2024, doctorate: false };
+		let ctx = ContextBuilder::default().year(2024).build().unwrap();
+		let young = ContextBuilder::default().year(2024).young_worker(worker).build().unwrap();
+		// The whole first year is exempt, so only SS is paid
+		let net = salary.yearly_plan_real_net(&young).unwrap().quantity();
+		assert_eq!(net, Money::new(21_000.0 - 2310.0));
+		assert!(salary.yearly_plan_real_net(&ctx).unwrap().quantity() < net);
+		// And withheld on: 40 IAS leave only 3% of each payment taxed, in the 0% bracket
+		assert_eq!(salary.yearly_plan_withhold_net(&young).unwrap().regular, Money::new(1500.0 - 165.0));
+		// 1500€ × 26% − 186.66€ otherwise
+		let withheld = Money::new(1500.0 - 165.0 - 203.34);
+		assert_eq!(salary.yearly_plan_withhold_net(&ctx).unwrap().regular, withheld);
+		// Until 2022 the exemption is only taken into account in the settlement
+		let worker = YoungWorker { first_year: 2022, ..worker };
+		let ctx = Context::default();
+		let young = ContextBuilder::default().young_worker(worker).build().unwrap();
+		assert_eq!(
+			salary.yearly_plan_withhold_net(&young).unwrap(),
+			salary.yearly_plan_withhold_net(&ctx).unwrap()
+		);
+		assert!(salary.yearly_plan_real_net(&young).unwrap() > salary.yearly_plan_real_net(&ctx).unwrap());
+	}
+}
@@ -1,6 +1,11 @@
 pub mod brackets;
+pub mod cat_b;
+pub mod jovem;
+pub mod settlement;
 pub mod withholding;
 
+use crate::units::TaxRate;
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Taxing {
 	None,
@@ -8,9 +13,12 @@ pub enum Taxing {
 	TaxedWitheld,
 }
 
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EstatutoFiscal {
 	/// Tributado pela globalidade dos rendimentos obtidos (em Portugal e no
 	/// estrangeiro)
+	#[default]
 	Residente,
 	/// Exclusão de tributação de 50% dos rendimentos do trabalho dependente
 	/// e dos rendimentos empresariais e profissionais.
@@ -19,8 +27,33 @@ pub enum EstatutoFiscal {
 	/// independente, a uma taxa fixa de 20%, relativamente aos rendimentos
 	/// derivados de atividades de "elevado valor acrescentado". Rendimentos
 	/// de fonte estrangeira podem ficar isentos de tributação, em
-	/// determinadas circunstâncias.
-	ResidenteNãoHabitual,
+	/// determinadas circunstâncias. From 2024, also the incentivo fiscal à
+	/// investigação científica e inovação (IFICI) that replaces it.
+	ResidenteNãoHabitual { elevado_valor_acrescentado: bool },
 	/// Tributado pelos rendimentos obtidos em Portugal.
 	NãoResidente,
 }
+
+impl EstatutoFiscal {
+	/// Share of category A and B income subject to IRS (CIRS, art. 12.º-A).
+	pub fn taxed_share(&self) -> f64 {
+		match self {
+			Self::ExResidente => 0.5,
+			_ => 1.0,
+		}
+	}
+
+	/// Flat rate taxing category A income instead of the brackets, which is
+	/// also the rate withheld (CIRS, art. 72.º and 99.º).
+	pub fn flat_rate(&self) -> Option<TaxRate> {
+		match self {
+			Self::ResidenteNãoHabitual { elevado_valor_acrescentado: true } => Some(0.20),
+			Self::NãoResidente => Some(0.25),
+			_ => None,
+		}
+	}
+
+	/// Non-residents are taxed on their gross income, without the dedução
+	/// específica.
+	pub fn specific_deduction(&self) -> bool { *self != Self::NãoResidente }
+}
@@ -0,0 +1,650 @@
+//! Annual IRS settlement (liquidação) of category A and B income.
+
+use crate::{
+	irs::brackets,
+	salary::Context,
+	tax_year::TaxYear,
+	units::{Money, MoneyRate, Rounding, TaxRate, Yearly},
+	Error, Result,
+};
+
+/// Deduction per dependent (CIRS, art. 78.º-A).
+const DEPENDENT_DEDUCTION: Money = Money::new(600.0);
+
+/// How the mínimo de existência shields low incomes from IRS (CIRS, art.
+/// 70.º).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MinimumExistence {
+	/// Until 2022, income net of tax can't fall below the valor de referência.
+	NetIncomeFloor,
+	/// From 2023, the taxable income is reduced by VR − 2 × (R − VR), when
+	/// positive, where VR is the valor de referência and R the gross income.
+	TaxableIncomeDeduction,
+}
+
+/// Expenses declared in the year, which give right to deductions from the tax
+/// (deduções à coleta).
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Expenses {
+	/// Despesas gerais familiares (art. 78.º-B).
+	pub general: Money,
+	/// Saúde (art. 78.º-C).
+	pub health: Money,
+	/// Educação e formação (art. 78.º-D).
+	pub education: Money,
+	/// Rendas da habitação permanente (art. 78.º-E).
+	pub rent: Money,
+	/// IVA suportado em faturas de setores com benefício (art. 78.º-F).
+	pub vat: Money,
+}
+
+/// Income of a taxpayer over the year.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Income {
+	/// Gross category A income.
+	pub gross: Money,
+	/// Part of `gross` exempt under IRS Jovem.
+	pub exempt: Money,
+	/// Mandatory SS contributions paid by the worker.
+	pub ss_contributions: Money,
+	/// IRS withheld during the year.
+	pub withheld: Money,
+	/// Taxable category B income, as given by
+	/// [`SelfEmployment::income`](super::cat_b::SelfEmployment::income).
+	pub cat_b: Money,
+	/// Part of `cat_b` exempt under IRS Jovem, which shares the cap with
+	/// `exempt`, as given by [`YoungWorker::exemptions`](super::jovem::YoungWorker::exemptions).
+	pub cat_b_exempt: Money,
+}
+
+/// Outcome of the annual settlement.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Settlement {
+	/// Gross category A income.
+	pub gross_income: Money,
+	/// Taxable category B income.
+	pub cat_b_income: Money,
+	/// Income exempt under IRS Jovem, which still counts to find the rate.
+	pub exempt_income: Money,
+	/// Income excluded from taxation by the estatuto fiscal, which doesn't
+	/// count to find the rate.
+	pub excluded_income: Money,
+	/// The larger of the year's dedução específica and the SS contributions
+	/// paid, up to the gross income (CIRS, art. 25.º).
+	pub specific_deduction: Money,
+	/// Rendimento coletável.
+	pub taxable_income: Money,
+	/// Tax, or taxable income from 2023, relieved by the mínimo de existência.
+	pub minimum_existence: Money,
+	/// Coleta: tax from the brackets, before deductions.
+	pub gross_tax: Money,
+	/// Deduções à coleta, up to the gross tax.
+	pub deductions: Money,
+	/// Taxa adicional de solidariedade, added to the tax after deductions.
+	pub surcharge: Money,
+	/// Tax due for the year.
+	pub tax: Money,
+	pub withheld: Money,
+}
+
+impl Settlement {
+	/// Refund from the tax authority, or the amount to pay if negative.
+	pub fn refund(&self) -> Money { self.withheld - self.tax }
+
+	/// Share of the gross income paid in tax.
+	pub fn effective_rate(&self) -> TaxRate {
+		if self.gross_income <= Money::zero() {
+			return 0.0;
+		}
+		self.tax.value() / self.gross_income.value()
+	}
+}
+
+/// Which of a couple's settlements is cheaper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Filing {
+	/// Each spouse is taxed on their own income.
+	Separate,
+	/// Tributação conjunta: the couple's taxable income is divided by 2 (quociente
+	/// conjugal), taxed, and multiplied back (CIRS, art. 69.º).
+	Joint,
+}
+
+/// A couple's settlements, taxed separately and jointly.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HouseholdSettlement {
+	pub separate: [Settlement; 2],
+	pub joint: Settlement,
+}
+
+impl HouseholdSettlement {
+	/// Tax the couple pays if taxed separately.
+	pub fn separate_tax(&self) -> Money { self.separate[0].tax + self.separate[1].tax }
+
+	pub fn cheapest(&self) -> Filing {
+		if self.joint.tax < self.separate_tax() {
+			Filing::Joint
+		} else {
+			Filing::Separate
+		}
+	}
+
+	/// Tax saved by choosing the cheapest filing over the other.
+	pub fn savings(&self) -> Money { (self.separate_tax() - self.joint.tax).abs() }
+}
+
+/// Settles a year of category A income, for a single taxpayer with the
+/// context's family.
+pub fn settle(income: &Income, expenses: &Expenses, ctx: &Context) -> Result<Settlement> {
+	let family = &ctx.family;
+	// Married taxpayers taxed separately each deduct half of the dependents
+	let dependents_share = if family.casado() && !family.titular_unico() { 0.5 } else { 1.0 };
+	settle_separately(income, expenses, dependents_share, ctx)
+}
+
+/// Settles the year of a married couple, or of a união de facto, both ways.
+/// Each spouse deducts half of the dependents when taxed separately.
+pub fn settle_household(spouses: &[(Income, Expenses); 2], ctx: &Context) -> Result<HouseholdSettlement> {
+	if !ctx.family.casado() {
+		return Err(Error::InvalidFamily("only couples can be taxed jointly".to_string()));
+	}
+	if ctx.estatuto_fiscal.flat_rate().is_some() {
+		return Err(Error::InvalidContext("income taxed at a flat rate can't be taxed jointly".to_string()));
+	}
+	let separate = [
+		settle_separately(&spouses[0].0, &spouses[0].1, 0.5, ctx)?,
+		settle_separately(&spouses[1].0, &spouses[1].1, 0.5, ctx)?,
+	];
+	let tax_year = ctx.tax_year()?;
+	let specific_deduction = separate[0].specific_deduction + separate[1].specific_deduction;
+	let gross_income = separate[0].gross_income + separate[1].gross_income;
+	let exempt_income = separate[0].exempt_income + separate[1].exempt_income;
+	let excluded_income = separate[0].excluded_income + separate[1].excluded_income;
+	let cat_b_income = separate[0].cat_b_income + separate[1].cat_b_income;
+	let taxed = gross_income + cat_b_income - exempt_income - excluded_income;
+	let taxable_income = separate[0].taxable_income + separate[1].taxable_income;
+	let quotient = MoneyRate::new(taxable_income / 2.0, Yearly);
+	let relief = taxable_income_relief(taxed / 2.0, quotient.quantity(), tax_year);
+	let gross_tax = taxes(quotient.quantity() - relief, exempt_income / 2.0, tax_year) * 2.0;
+	let expenses = Expenses {
+		general: Money::zero(),
+		health: spouses[0].1.health + spouses[1].1.health,
+		education: spouses[0].1.education + spouses[1].1.education,
+		rent: spouses[0].1.rent + spouses[1].1.rent,
+		vat: spouses[0].1.vat + spouses[1].1.vat,
+	};
+	let deductions = dependents_deduction(1.0, ctx)
+		+ general_deduction(&spouses[0].1)
+		+ general_deduction(&spouses[1].1)
+		+ capped_deductions(&expenses, quotient.quantity(), ctx)?;
+	let deductions = deductions.min(gross_tax);
+	let tax = gross_tax - deductions;
+	let tax_relief = tax_relief(taxed / 2.0, tax / 2.0, tax_year) * 2.0;
+	let surcharge = brackets::solidarity_surcharge(quotient).quantity() * 2.0;
+	let joint = Settlement {
+		gross_income,
+		cat_b_income,
+		exempt_income,
+		excluded_income,
+		specific_deduction,
+		taxable_income,
+		minimum_existence: relief * 2.0 + tax_relief,
+		gross_tax,
+		deductions,
+		surcharge,
+		tax: tax - tax_relief + surcharge,
+		withheld: separate[0].withheld + separate[1].withheld,
+	};
+	Ok(HouseholdSettlement { separate, joint })
+}
+
+fn settle_separately(
+	income: &Income, expenses: &Expenses, dependents_share: f64, ctx: &Context,
+) -> Result<Settlement> {
+	let tax_year = ctx.tax_year()?;
+	let estatuto = ctx.estatuto_fiscal;
+	let excluded = ((income.gross - income.exempt) * (1.0 - estatuto.taxed_share())).round(Rounding::HalfUp);
+	let taxed = income.gross - income.exempt - excluded;
+	let cat_b = ((income.cat_b - income.cat_b_exempt) * estatuto.taxed_share()).round(Rounding::HalfUp);
+	let excluded = excluded + income.cat_b - income.cat_b_exempt - cat_b;
+	let exempt = income.exempt + income.cat_b_exempt;
+	let specific_deduction = match estatuto.specific_deduction() {
+		true => tax_year.specific_deduction.max(income.ss_contributions).min(taxed.max(Money::zero())),
+		false => Money::zero(),
+	};
+	let taxable_income = taxed - specific_deduction + cat_b;
+	if let Some(rate) = estatuto.flat_rate() {
+		let tax = (taxable_income.max(Money::zero()) * rate).round(Rounding::HalfUp);
+		return Ok(Settlement {
+			gross_income: income.gross,
+			cat_b_income: income.cat_b,
+			exempt_income: exempt,
+			excluded_income: excluded,
+			specific_deduction,
+			taxable_income,
+			minimum_existence: Money::zero(),
+			gross_tax: tax,
+			deductions: Money::zero(),
+			surcharge: Money::zero(),
+			tax,
+			withheld: income.withheld,
+		});
+	}
+	let relief = taxable_income_relief(taxed + cat_b, taxable_income, tax_year);
+	let gross_tax = taxes(taxable_income - relief, exempt, tax_year);
+	let deductions = dependents_deduction(dependents_share, ctx)
+		+ general_deduction(expenses)
+		+ capped_deductions(expenses, taxable_income, ctx)?;
+	let deductions = deductions.min(gross_tax);
+	let tax = gross_tax - deductions;
+	let tax_relief = tax_relief(taxed + cat_b, tax, tax_year);
+	let surcharge = brackets::solidarity_surcharge(MoneyRate::new(taxable_income, Yearly)).quantity();
+	Ok(Settlement {
+		gross_income: income.gross,
+		cat_b_income: income.cat_b,
+		exempt_income: exempt,
+		excluded_income: excluded,
+		specific_deduction,
+		taxable_income,
+		minimum_existence: relief + tax_relief,
+		gross_tax,
+		deductions,
+		surcharge,
+		tax: tax - tax_relief + surcharge,
+		withheld: income.withheld,
+	})
+}
+
+/// Tax from the brackets on a taxable income, at the average rate of that
+/// income plus the exempt income, which only counts to find the rate (CIRS,
+/// art. 12.º-B).
+fn taxes(taxable_income: Money, exempt_income: Money, tax_year: &TaxYear) -> Money {
+	let taxable = MoneyRate::new(taxable_income, Yearly);
+	if exempt_income <= Money::zero() || taxable_income <= Money::zero() {
+		return brackets::taxes(taxable, tax_year.brackets).quantity();
+	}
+	let with_exempt = taxable + MoneyRate::new(exempt_income, Yearly);
+	let tax = brackets::taxes(with_exempt, tax_year.brackets).quantity();
+	(tax * (taxable_income.value() / with_exempt.quantity().value())).round(Rounding::HalfUp)
+}
+
+/// Mínimo de existência from 2023: how much the taxable income is reduced.
+fn taxable_income_relief(gross_income: Money, taxable_income: Money, tax_year: &TaxYear) -> Money {
+	if tax_year.minimum_existence != MinimumExistence::TaxableIncomeDeduction {
+		return Money::zero();
+	}
+	let reference = tax_year.minimum_existence_reference();
+	let relief = (reference - (gross_income - reference) * 2.0).max(Money::zero());
+	relief.min(taxable_income.max(Money::zero()))
+}
+
+/// Mínimo de existência until 2022: how much the tax is reduced so that the
+/// income net of tax doesn't fall below the valor de referência.
+fn tax_relief(gross_income: Money, tax: Money, tax_year: &TaxYear) -> Money {
+	if tax_year.minimum_existence != MinimumExistence::NetIncomeFloor {
+		return Money::zero();
+	}
+	let most = (gross_income - tax_year.minimum_existence_reference()).max(Money::zero());
+	(tax - most).max(Money::zero())
+}
+
+fn deduct(expense: Money, rate: f64, limit: Money) -> Money {
+	(expense * rate).round(Rounding::HalfUp).min(limit)
+}
+
+fn dependents_deduction(share: f64, ctx: &Context) -> Money {
+	DEPENDENT_DEDUCTION * (ctx.family.dependentes() as f64 * share)
+}
+
+/// General family expenses are capped per taxpayer, even when taxed jointly.
+fn general_deduction(expenses: &Expenses) -> Money { deduct(expenses.general, 0.35, Money::new(250.0)) }
+
+/// Deductions whose sum is capped on higher incomes, given the taxable income
+/// per taxpayer.
+fn capped_deductions(expenses: &Expenses, taxable_income: Money, ctx: &Context) -> Result<Money> {
+	let tax_year = ctx.tax_year()?;
+	let capped = deduct(expenses.health, 0.15, Money::new(1000.0))
+		+ deduct(expenses.education, 0.30, Money::new(800.0))
+		+ deduct(expenses.rent, 0.15, tax_year.rent_deduction_limit)
+		+ deduct(expenses.vat, 0.15, Money::new(250.0));
+	let lower = tax_year.brackets[0].rate().map_or(Money::zero(), |rate| rate.quantity());
+	let cap = if taxable_income <= lower {
+		None
+	} else if taxable_income > tax_year.deductions_cap_income {
+		Some(Money::new(1000.0))
+	} else {
+		let upper = tax_year.deductions_cap_income;
+		let share = (upper - taxable_income).value() / (upper - lower).value();
+		Some(Money::new(1000.0 + 1500.0 * share))
+	};
+	// Households with three or more dependents get 5% more per dependent
+	let cap = cap.map(|cap| match ctx.family.dependentes() {
+		n @ 3.. => cap * (1.0 + 0.05 * n as f64),
+		_ => cap,
+	});
+	Ok(cap.map_or(capped, |cap| capped.min(cap.round(Rounding::HalfUp))))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		irs::{
+			cat_b::{Activity, Receipts, SelfEmployment},
+			jovem::YoungWorker,
+			EstatutoFiscal,
+		},
+		salary::{ContextBuilder, MealAllowance, Salary},
+		FamilyElement,
+	};
+
+	#[test]
+	fn specific_deduction() {
+		let ctx = Context::default();
+		let income = Income {
+			gross: Money::new(14_000.0),
+			ss_contributions: Money::new(1540.0),
+			..Default::default()
+		};
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		assert_eq!(settlement.specific_deduction, Money::new(4104.0));
+		assert_eq!(settlement.taxable_income, Money::new(9896.0));
+		// 7116€ at 14.5% and 2780€ at 23%
+		assert_eq!(settlement.gross_tax, Money::new(1031.82 + 639.40));
+		// SS contributions above the dedução específica are deducted instead
+		let income = Income { gross: Money::new(70_000.0), ss_contributions: Money::new(7700.0), ..income };
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		assert_eq!(settlement.specific_deduction, Money::new(7700.0));
+		// Nothing to deduct from a small income
+		let income = Income { gross: Money::new(3000.0), ss_contributions: Money::new(330.0), ..income };
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		assert_eq!(settlement.specific_deduction, Money::new(3000.0));
+		assert_eq!(settlement.tax, Money::zero());
+	}
+
+	#[test]
+	fn deductions_from_the_tax() {
+		let family = FamilyElement::new(false, false, 2).unwrap();
+		let ctx = ContextBuilder::default().family(family).build().unwrap();
+		let income = Income {
+			gross: Money::new(30_000.0),
+			ss_contributions: Money::new(3300.0),
+			withheld: Money::new(4000.0),
+			..Default::default()
+		};
+		let expenses = Expenses {
+			general: Money::new(2000.0),
+			health: Money::new(1000.0),
+			education: Money::new(500.0),
+			rent: Money::new(6000.0),
+			vat: Money::zero(),
+		};
+		let settlement = settle(&income, &expenses, &ctx).unwrap();
+		// 1200€ for the dependents, 250€ of general expenses, and 150€ + 150€
+		// + 502€ under the cap for a 25896€ taxable income
+		assert_eq!(settlement.deductions, Money::new(1200.0 + 250.0 + 150.0 + 150.0 + 502.0));
+		assert_eq!(settlement.tax, settlement.gross_tax - settlement.deductions);
+		assert_eq!(settlement.refund(), Money::new(4000.0) - settlement.tax);
+		// Deductions never exceed the tax
+		let income = Income { gross: Money::new(8000.0), ss_contributions: Money::new(880.0), ..income };
+		let settlement = settle(&income, &expenses, &ctx).unwrap();
+		assert_eq!(settlement.tax, Money::zero());
+	}
+
+	#[test]
+	fn deductions_cap_follows_the_year() {
+		let expenses = Expenses {
+			health: Money::new(10_000.0),
+			education: Money::new(10_000.0),
+			..Default::default()
+		};
+		let capped = |year: u32| {
+			let ctx = ContextBuilder::default().year(year).build().unwrap();
+			capped_deductions(&expenses, Money::new(81_000.0), &ctx).unwrap()
+		};
+		// Above the 80 882€ of 2022, but below the 83 696€ of 2025
+		assert_eq!(capped(2022), Money::new(1000.0));
+		assert_eq!(capped(2025), Money::new(1053.47));
+	}
+
+	#[test]
+	fn salary_settlement() {
+		let ctx = Context::default();
+		let salary = Salary::new(1500.0, MealAllowance::None, 0.0);
+		let settlement = salary.settlement(&Expenses::default(), &ctx).unwrap();
+		assert_eq!(settlement.gross_income, Money::new(21_000.0));
+		assert_eq!(settlement.withheld, salary.timeline(&ctx).unwrap().irs().quantity());
+		assert!(settlement.refund() > Money::zero());
+	}
+
+	#[test]
+	fn joint_taxation() {
+		let family = FamilyElement::new(true, false, 1).unwrap();
+		let ctx = ContextBuilder::default().family(family).build().unwrap();
+		let income = |gross: f64| Income {
+			gross: Money::new(gross),
+			ss_contributions: Money::new(gross * 0.11),
+			..Default::default()
+		};
+		// Very unequal incomes are cheaper to tax jointly
+		let spouses = [(income(50_000.0), Expenses::default()), (income(0.0), Expenses::default())];
+		let household = settle_household(&spouses, &ctx).unwrap();
+		assert_eq!(household.joint.taxable_income, Money::new(50_000.0 - 5500.0));
+		let quotient =
+			brackets::taxes(MoneyRate::new(22_250.0, Yearly), TaxYear::get(2022).unwrap().brackets);
+		assert_eq!(household.joint.gross_tax, quotient.quantity() * 2.0);
+		assert_eq!(household.joint.deductions, Money::new(600.0));
+		assert_eq!(household.separate[0].deductions, Money::new(300.0));
+		assert_eq!(household.cheapest(), Filing::Joint);
+		assert_eq!(household.savings(), household.separate_tax() - household.joint.tax);
+		// Equal incomes pay the same, but separately each deducts half of the
+		// dependents either way
+		let spouses = [(income(20_000.0), Expenses::default()), (income(20_000.0), Expenses::default())];
+		let household = settle_household(&spouses, &ctx).unwrap();
+		assert_eq!(household.joint.tax, household.separate_tax());
+		assert_eq!(household.cheapest(), Filing::Separate);
+		// Only couples
+		let ctx = Context::default();
+		assert!(matches!(settle_household(&spouses, &ctx), Err(Error::InvalidFamily(_))));
+	}
+
+	#[test]
+	fn minimum_existence() {
+		let minimum_wage = |year| {
+			let ctx = ContextBuilder::default().year(year).build().unwrap();
+			let gross = ctx.tax_year().unwrap().minimum_wage.as_yearly().quantity();
+			let income = Income { gross, ss_contributions: gross * 0.11, ..Default::default() };
+			settle(&income, &Expenses::default(), &ctx).unwrap()
+		};
+		for year in 2019..=2026 {
+			assert_eq!(minimum_wage(year).tax, Money::zero(), "{year}");
+		}
+		// Until 2022, the tax is cut down to what's earned above the floor
+		let ctx = Context::default();
+		let income = Income {
+			gross: Money::new(10_000.0),
+			ss_contributions: Money::new(1100.0),
+			..Default::default()
+		};
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		assert_eq!(settlement.tax, Money::new(130.0));
+		assert_eq!(settlement.gross_tax - settlement.tax, settlement.minimum_existence);
+		// From 2023, the taxable income is reduced on a sliding scale
+		let ctx = ContextBuilder::default().year(2023).build().unwrap();
+		let income = Income { gross: Money::new(14_000.0), ss_contributions: Money::new(1540.0), ..income };
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		assert_eq!(settlement.minimum_existence, Money::new(10_640.0 - 2.0 * 3360.0));
+		let income = Income { gross: Money::new(16_000.0), ss_contributions: Money::new(1760.0), ..income };
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		assert_eq!(settlement.minimum_existence, Money::zero());
+	}
+
+	#[test]
+	fn minimum_wage_real_net() {
+		for year in 2019..=2026 {
+			let ctx = ContextBuilder::default().year(year).build().unwrap();
+			let minimum_wage = ctx.tax_year().unwrap().minimum_wage.quantity();
+			let salary = Salary::new(minimum_wage, MealAllowance::None, 0.0);
+			let net = salary.yearly_plan_real_net(&ctx).unwrap().quantity();
+			assert_eq!(net, (minimum_wage - (minimum_wage * 0.11).round(Rounding::HalfUp)) * 14.0, "{year}");
+		}
+	}
+
+	#[test]
+	fn solidarity_surcharge() {
+		let ctx = Context::default();
+		let settle = |gross: f64| {
+			let income = Income {
+				gross: Money::new(gross),
+				ss_contributions: Money::new(gross * 0.11),
+				..Default::default()
+			};
+			settle(&income, &Expenses::default(), &ctx).unwrap()
+		};
+		assert_eq!(settle(89_000.0).surcharge, Money::zero());
+		// 26800€ above 80k at 2.5%
+		let settlement = settle(120_000.0);
+		assert_eq!(settlement.surcharge, Money::new(670.0));
+		assert_eq!(settlement.tax, settlement.gross_tax - settlement.deductions + settlement.surcharge);
+		// 170k at 2.5% and 17k above 250k at 5%
+		assert_eq!(settle(300_000.0).surcharge, Money::new(4250.0 + 850.0));
+	}
+
+	#[test]
+	fn irs_jovem() {
+		let ctx = Context::default();
+		let income = Income {
+			gross: Money::new(20_000.0),
+			ss_contributions: Money::new(2200.0),
+			..Default::default()
+		};
+		let full = settle(&income, &Expenses::default(), &ctx).unwrap();
+		let income = Income { exempt: Money::new(3324.0), ..income };
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		assert_eq!(settlement.taxable_income, full.taxable_income - Money::new(3324.0));
+		// The exempt income still sets the rate
+		let rate = full.gross_tax.value() / full.taxable_income.value();
+		let expected = Money::new(rate * settlement.taxable_income.value()).round(Rounding::HalfUp);
+		assert!((settlement.gross_tax - expected).abs() <= Money::new(0.01));
+	}
+
+	#[test]
+	fn estatuto_fiscal() {
+		let settle = |estatuto_fiscal| {
+			let ctx = ContextBuilder::default().estatuto_fiscal(estatuto_fiscal).build().unwrap();
+			let income = Income {
+				gross: Money::new(50_000.0),
+				ss_contributions: Money::new(5500.0),
+				..Default::default()
+			};
+			settle(&income, &Expenses::default(), &ctx).unwrap()
+		};
+		// Half the income is excluded for ex-residents
+		let settlement = settle(EstatutoFiscal::ExResidente);
+		assert_eq!(settlement.excluded_income, Money::new(25_000.0));
+		assert_eq!(settlement.taxable_income, Money::new(25_000.0 - 5500.0));
+		assert!(settlement.tax < settle(EstatutoFiscal::Residente).tax);
+		// 20% of the net income in high-value activities
+		let settlement = settle(EstatutoFiscal::ResidenteNãoHabitual { elevado_valor_acrescentado: true });
+		assert_eq!(settlement.tax, Money::new(44_500.0 * 0.2));
+		let settlement = settle(EstatutoFiscal::ResidenteNãoHabitual { elevado_valor_acrescentado: false });
+		assert_eq!(settlement.tax, settle(EstatutoFiscal::Residente).tax);
+		// 25% of the gross income for non-residents
+		assert_eq!(settle(EstatutoFiscal::NãoResidente).tax, Money::new(12_500.0));
+	}
+
+	#[test]
+	fn estatuto_fiscal_withholding() {
+		let salary = Salary::new(2000.0, MealAllowance::None, 0.0);
+		let withheld = |estatuto_fiscal| {
+			let ctx = ContextBuilder::default().estatuto_fiscal(estatuto_fiscal).build().unwrap();
+			salary.timeline(&ctx).unwrap().month(1).unwrap().irs
+		};
+		assert_eq!(withheld(EstatutoFiscal::NãoResidente), Money::new(500.0));
+		let rate = TaxYear::get(2022).unwrap().withholding_tables(1).unwrap();
+		let rate = rate.tax(Money::new(1000.0), &FamilyElement::default()).unwrap();
+		assert_eq!(
+			withheld(EstatutoFiscal::ExResidente),
+			(Money::new(1000.0) * rate).round(Rounding::Truncate)
+		);
+	}
+
+	#[test]
+	fn cat_b_income() {
+		let ctx = Context::default();
+		let activity = SelfEmployment {
+			receipts: vec![Receipts { activity: Activity::ListedProfession, gross: Money::new(20_000.0) }],
+			..Default::default()
+		};
+		let cat_b = activity.income(Money::zero(), Money::zero(), ctx.tax_year().unwrap()).unwrap().taxable();
+		let income = Income { cat_b, ..Default::default() };
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		// No dedução específica on category B income
+		assert_eq!(settlement.taxable_income, Money::new(15_000.0));
+		let brackets = TaxYear::get(2022).unwrap().brackets;
+		assert_eq!(
+			settlement.gross_tax,
+			brackets::taxes(MoneyRate::new(15_000.0, Yearly), brackets).quantity()
+		);
+	}
+
+	#[test]
+	fn cat_a_and_cat_b_income() {
+		let ctx = Context::default();
+		let tax_year = ctx.tax_year().unwrap();
+		let activity = SelfEmployment {
+			receipts: vec![Receipts { activity: Activity::ListedProfession, gross: Money::new(20_000.0) }],
+			..Default::default()
+		};
+		let (gross, ss_contributions) = (Money::new(10_000.0), Money::new(1100.0));
+		let cat_b = activity.income(gross, ss_contributions, tax_year).unwrap().taxable();
+		let income = Income { gross, ss_contributions, cat_b, ..Default::default() };
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		// The 4104€ are deducted from category A income, so the 3000€ of expenses
+		// to justify on services are all added back
+		assert_eq!(cat_b, Money::new(15_000.0 + 3000.0));
+		assert_eq!(settlement.taxable_income, Money::new(10_000.0 - 4104.0 + 18_000.0));
+	}
+
+	#[test]
+	fn young_freelancer() {
+		let ctx = ContextBuilder::default().year(2025).build().unwrap();
+		let tax_year = ctx.tax_year().unwrap();
+		let worker = YoungWorker { age: 27, first_year: 2025, doctorate: false };
+		let activity = SelfEmployment {
+			receipts: vec![Receipts { activity: Activity::ListedProfession, gross: Money::new(20_000.0) }],
+			..Default::default()
+		};
+		let cat_b = activity.income(Money::zero(), Money::zero(), tax_year).unwrap().taxable();
+		assert_eq!(cat_b, Money::new(15_000.0));
+		// The first year is wholly exempt, up to 55 IAS
+		let (exempt, cat_b_exempt) = worker.exemptions(Money::zero(), cat_b, tax_year);
+		assert_eq!((exempt, cat_b_exempt), (Money::zero(), cat_b));
+		let income = Income { cat_b, cat_b_exempt, ..Default::default() };
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		assert_eq!(settlement.exempt_income, cat_b);
+		assert_eq!(settlement.taxable_income, Money::zero());
+		assert_eq!(settlement.tax, Money::zero());
+		// With a salary too, both share the 28 737.50€ cap in proportion
+		let gross = Money::new(20_000.0);
+		let (exempt, cat_b_exempt) = worker.exemptions(gross, cat_b, tax_year);
+		assert_eq!(exempt + cat_b_exempt, Money::new(522.50 * 55.0));
+		assert_eq!(cat_b_exempt, Money::new(12_316.07));
+		let income = Income { gross, exempt, ss_contributions: Money::new(2200.0), cat_b_exempt, ..income };
+		let settlement = settle(&income, &Expenses::default(), &ctx).unwrap();
+		// What's left of category A is under the dedução específica
+		assert_eq!(settlement.taxable_income, cat_b - cat_b_exempt);
+		let taxed = Income { exempt: Money::zero(), cat_b_exempt: Money::zero(), ..income };
+		assert!(settlement.tax < settle(&taxed, &Expenses::default(), &ctx).unwrap().tax);
+		// Until 2021, only category A income was covered
+		let tax_year = TaxYear::get(2021).unwrap();
+		let worker = YoungWorker { age: 25, first_year: 2021, ..worker };
+		let (exempt, cat_b_exempt) = worker.exemptions(Money::new(10_000.0), cat_b, tax_year);
+		assert_eq!((exempt, cat_b_exempt), (Money::new(3000.0), Money::zero()));
+	}
+}
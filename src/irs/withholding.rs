@@ -1,47 +1,420 @@
-use crate::{FamilyElement, Money, TaxRate};
+use crate::{Error, FamilyElement, Money, Result, TaxRate};
 
-// You can obtain these tables by applying tabler.sh to the
+// You can obtain the tables up to 2022 by applying tabler.sh to the
 // table selection at www.economias.pt/docs/tabelas_irs_YYYY.pdf:
 
+/// Withholding table for a family situation.
+#[derive(Debug)]
+pub enum Table {
+	/// Rate in percent by monthly income, for 0 to 5 or more dependents.
+	Rates(&'static [(f64, [TaxRate; 6])]),
+	/// Marginal rate on the monthly income, minus a parcela a abater for the
+	/// bracket and another for each dependent, as in the tables since July
+	/// 2023.
+	Marginal { brackets: &'static [MarginalBracket], per_dependent: f64 },
+}
+
+/// Bracket of a marginal table, for monthly incomes up to `limit`.
+#[derive(Debug)]
+pub struct MarginalBracket {
+	pub limit: f64,
+	/// Marginal rate, in percent.
+	pub rate: TaxRate,
+	pub parcela: Parcela,
+}
+
+/// Parcela a abater of a bracket.
+#[derive(Debug)]
+pub enum Parcela {
+	Fixed(f64),
+	/// `rate × factor × (reference − income)`, so that withholding doesn't
+	/// jump when leaving the exempt bracket.
+	Variable {
+		factor: f64,
+		reference: f64,
+	},
+}
+
+impl MarginalBracket {
+	const fn new(limit: f64, rate: TaxRate, parcela: Parcela) -> Self { Self { limit, rate, parcela } }
+
+	/// Amount withheld on `income` in this bracket, before dependents.
+	fn withholding(&self, income: f64) -> f64 {
+		let rate = self.rate / 100.0;
+		let parcela = match self.parcela {
+			Parcela::Fixed(parcela) => parcela,
+			Parcela::Variable { factor, reference } => rate * factor * (reference - income),
+		};
+		income * rate - parcela
+	}
+}
+
+/// Tables for each family situation, in force from some month of the year.
+#[derive(Debug)]
 pub struct RetentionTables {
-	nao_casado: &'static [(f64, [TaxRate; 6])],
-	casado_titular_unico: &'static [(f64, [TaxRate; 6])],
-	casado_dois_titulares: &'static [(f64, [TaxRate; 6])],
+	from_month: u32,
+	nao_casado: &'static Table,
+	casado_titular_unico: &'static Table,
+	casado_dois_titulares: &'static Table,
 }
 
 impl RetentionTables {
-	pub fn for_family(&self, family: &FamilyElement) -> &'static [(f64, [TaxRate; 6])] {
-		if family.casado {
-			if family.titular_unico {
-				self.casado_titular_unico
-			} else {
-				self.casado_dois_titulares
-			}
-		} else {
-			self.nao_casado
+	/// First month the tables apply to.
+	pub fn from_month(&self) -> u32 { self.from_month }
+
+	pub fn for_family(&self, family: &FamilyElement) -> &'static Table {
+		match (family.casado, family.titular_unico) {
+			(false, _) => self.nao_casado,
+			(true, true) => self.casado_titular_unico,
+			(true, false) => self.casado_dois_titulares,
 		}
 	}
 
-	pub fn tax(&self, gross_monthly: Money, family: &FamilyElement) -> TaxRate {
+	/// Rate withheld on `gross_monthly`. With marginal tables, that's the
+	/// effective rate, which is also the one twelfths are withheld at.
+	pub fn tax(&self, gross_monthly: Money, family: &FamilyElement) -> Result<TaxRate> {
 		let dependentes = family.dependentes;
-		for l in self.for_family(family).iter() {
-			if l.0 >= gross_monthly.value() {
-				return l.1[std::cmp::min(5, dependentes)] / 100.0;
+		let missing = || Error::MissingTable(format!("withholding rate for {}", gross_monthly));
+		match self.for_family(family) {
+			Table::Rates(table) => {
+				for l in table.iter() {
+					if l.0 >= gross_monthly.value() {
+						return Ok(l.1[std::cmp::min(5, dependentes)] / 100.0);
+					}
+				}
+				Err(missing())
+			}
+			Table::Marginal { brackets, per_dependent } => {
+				let income = gross_monthly.value();
+				let bracket = brackets.iter().find(|b| b.limit >= income).ok_or_else(missing)?;
+				let withheld = bracket.withholding(income) - dependentes as f64 * per_dependent;
+				Ok(if income > 0.0 { withheld.max(0.0) / income } else { 0.0 })
 			}
 		}
-		unreachable!();
 	}
 }
 
+/// Tables in force from January. Tabela III, for married couples with a single
+/// holder, is approximated by Tabela I on 83% of the income.
+pub mod year_2026 {
+	use super::*;
+	pub const tables: [RetentionTables; 1] = [RetentionTables {
+		from_month: 1,
+		nao_casado: &Table::Marginal { brackets: &brackets, per_dependent: 34.29 },
+		casado_titular_unico: &Table::Marginal { brackets: &titular_unico, per_dependent: 42.86 },
+		casado_dois_titulares: &Table::Marginal { brackets: &brackets, per_dependent: 21.43 },
+	}];
+
+	pub const brackets: [MarginalBracket; 14] = [
+		MarginalBracket::new(920.00, 0.0, Parcela::Fixed(0.0)),
+		MarginalBracket::new(1_027.00, 12.5, Parcela::Variable { factor: 2.6, reference: 1_273.85 }),
+		MarginalBracket::new(1_109.00, 15.7, Parcela::Variable { factor: 1.35, reference: 1_560.57 }),
+		MarginalBracket::new(1_195.00, 15.7, Parcela::Fixed(95.71)),
+		MarginalBracket::new(1_374.00, 21.2, Parcela::Fixed(161.44)),
+		MarginalBracket::new(1_639.00, 24.1, Parcela::Fixed(201.29)),
+		MarginalBracket::new(2_039.00, 31.1, Parcela::Fixed(316.02)),
+		MarginalBracket::new(2_723.00, 34.9, Parcela::Fixed(393.50)),
+		MarginalBracket::new(3_205.00, 38.72, Parcela::Fixed(497.52)),
+		MarginalBracket::new(4_444.00, 40.05, Parcela::Fixed(540.15)),
+		MarginalBracket::new(5_682.00, 41.72, Parcela::Fixed(614.36)),
+		MarginalBracket::new(6_783.00, 42.77, Parcela::Fixed(674.02)),
+		MarginalBracket::new(20_067.00, 44.95, Parcela::Fixed(821.89)),
+		MarginalBracket::new(f64::INFINITY, 47.17, Parcela::Fixed(1_267.38)),
+	];
+
+	pub const titular_unico: [MarginalBracket; 14] = [
+		MarginalBracket::new(1_108.00, 0.0, Parcela::Fixed(0.0)),
+		MarginalBracket::new(1_237.00, 12.5, Parcela::Variable { factor: 2.6, reference: 1_534.15 }),
+		MarginalBracket::new(1_336.00, 15.7, Parcela::Variable { factor: 1.35, reference: 1_879.41 }),
+		MarginalBracket::new(1_440.00, 15.7, Parcela::Fixed(115.18)),
+		MarginalBracket::new(1_655.00, 21.2, Parcela::Fixed(194.38)),
+		MarginalBracket::new(1_975.00, 24.1, Parcela::Fixed(242.38)),
+		MarginalBracket::new(2_457.00, 31.1, Parcela::Fixed(380.63)),
+		MarginalBracket::new(3_281.00, 34.9, Parcela::Fixed(474.00)),
+		MarginalBracket::new(3_861.00, 38.72, Parcela::Fixed(599.33)),
+		MarginalBracket::new(5_354.00, 40.05, Parcela::Fixed(650.68)),
+		MarginalBracket::new(6_846.00, 41.72, Parcela::Fixed(740.09)),
+		MarginalBracket::new(8_172.00, 42.77, Parcela::Fixed(811.97)),
+		MarginalBracket::new(20_067.00, 44.95, Parcela::Fixed(990.12)),
+		MarginalBracket::new(f64::INFINITY, 47.17, Parcela::Fixed(1_435.61)),
+	];
+}
+
+/// Tables in force from January. Tabela III, for married couples with a single
+/// holder, is approximated by Tabela I on 83% of the income.
+pub mod year_2025 {
+	use super::*;
+	pub const tables: [RetentionTables; 1] = [RetentionTables {
+		from_month: 1,
+		nao_casado: &Table::Marginal { brackets: &brackets, per_dependent: 34.29 },
+		casado_titular_unico: &Table::Marginal { brackets: &titular_unico, per_dependent: 42.86 },
+		casado_dois_titulares: &Table::Marginal { brackets: &brackets, per_dependent: 21.43 },
+	}];
+
+	pub const brackets: [MarginalBracket; 14] = [
+		MarginalBracket::new(870.00, 0.0, Parcela::Fixed(0.0)),
+		MarginalBracket::new(992.00, 13.0, Parcela::Variable { factor: 2.6, reference: 1_204.62 }),
+		MarginalBracket::new(1_071.00, 16.5, Parcela::Variable { factor: 1.35, reference: 1_470.50 }),
+		MarginalBracket::new(1_154.00, 16.5, Parcela::Fixed(88.99)),
+		MarginalBracket::new(1_327.00, 22.0, Parcela::Fixed(152.46)),
+		MarginalBracket::new(1_583.00, 25.0, Parcela::Fixed(192.27)),
+		MarginalBracket::new(1_970.00, 32.0, Parcela::Fixed(303.08)),
+		MarginalBracket::new(2_631.00, 35.5, Parcela::Fixed(372.03)),
+		MarginalBracket::new(3_096.00, 38.72, Parcela::Fixed(456.75)),
+		MarginalBracket::new(4_293.00, 40.05, Parcela::Fixed(497.93)),
+		MarginalBracket::new(5_489.00, 41.72, Parcela::Fixed(569.62)),
+		MarginalBracket::new(6_553.00, 42.77, Parcela::Fixed(627.25)),
+		MarginalBracket::new(20_067.00, 44.95, Parcela::Fixed(770.11)),
+		MarginalBracket::new(f64::INFINITY, 47.17, Parcela::Fixed(1_215.60)),
+	];
+
+	pub const titular_unico: [MarginalBracket; 14] = [
+		MarginalBracket::new(1_048.00, 0.0, Parcela::Fixed(0.0)),
+		MarginalBracket::new(1_195.00, 13.0, Parcela::Variable { factor: 2.6, reference: 1_451.08 }),
+		MarginalBracket::new(1_290.00, 16.5, Parcela::Variable { factor: 1.35, reference: 1_771.34 }),
+		MarginalBracket::new(1_390.00, 16.5, Parcela::Fixed(107.22)),
+		MarginalBracket::new(1_599.00, 22.0, Parcela::Fixed(183.67)),
+		MarginalBracket::new(1_907.00, 25.0, Parcela::Fixed(231.64)),
+		MarginalBracket::new(2_373.00, 32.0, Parcela::Fixed(365.13)),
+		MarginalBracket::new(3_170.00, 35.5, Parcela::Fixed(448.18)),
+		MarginalBracket::new(3_730.00, 38.72, Parcela::Fixed(550.25)),
+		MarginalBracket::new(5_172.00, 40.05, Parcela::Fixed(599.86)),
+		MarginalBracket::new(6_613.00, 41.72, Parcela::Fixed(686.23)),
+		MarginalBracket::new(7_895.00, 42.77, Parcela::Fixed(755.67)),
+		MarginalBracket::new(20_067.00, 44.95, Parcela::Fixed(927.78)),
+		MarginalBracket::new(f64::INFINITY, 47.17, Parcela::Fixed(1_373.27)),
+	];
+}
+
+/// Tables in force from January to August. From September, lower rates
+/// made up for the cut in the year's IRS rates. Tabela III, for married
+/// couples with a single holder, is approximated by Tabela I on 83% of the
+/// income.
+pub mod year_2024 {
+	use super::*;
+	pub const tables: [RetentionTables; 1] = [RetentionTables {
+		from_month: 1,
+		nao_casado: &Table::Marginal { brackets: &brackets, per_dependent: 34.29 },
+		casado_titular_unico: &Table::Marginal { brackets: &titular_unico, per_dependent: 42.86 },
+		casado_dois_titulares: &Table::Marginal { brackets: &brackets, per_dependent: 21.43 },
+	}];
+
+	pub const brackets: [MarginalBracket; 13] = [
+		MarginalBracket::new(820.00, 0.0, Parcela::Fixed(0.0)),
+		MarginalBracket::new(935.00, 13.25, Parcela::Variable { factor: 2.6, reference: 1_135.39 }),
+		MarginalBracket::new(1_001.00, 18.0, Parcela::Variable { factor: 1.4, reference: 1_385.20 }),
+		MarginalBracket::new(1_123.00, 18.0, Parcela::Fixed(96.82)),
+		MarginalBracket::new(1_765.00, 26.0, Parcela::Fixed(186.66)),
+		MarginalBracket::new(2_057.00, 32.75, Parcela::Fixed(305.80)),
+		MarginalBracket::new(2_664.00, 37.0, Parcela::Fixed(393.23)),
+		MarginalBracket::new(3_193.00, 38.72, Parcela::Fixed(439.05)),
+		MarginalBracket::new(4_173.00, 40.05, Parcela::Fixed(481.52)),
+		MarginalBracket::new(5_470.00, 41.72, Parcela::Fixed(551.21)),
+		MarginalBracket::new(6_540.00, 42.76, Parcela::Fixed(608.10)),
+		MarginalBracket::new(20_067.00, 44.05, Parcela::Fixed(692.47)),
+		MarginalBracket::new(f64::INFINITY, 47.17, Parcela::Fixed(1_318.56)),
+	];
+
+	pub const titular_unico: [MarginalBracket; 13] = [
+		MarginalBracket::new(988.00, 0.0, Parcela::Fixed(0.0)),
+		MarginalBracket::new(1_127.00, 13.25, Parcela::Variable { factor: 2.6, reference: 1_368.00 }),
+		MarginalBracket::new(1_206.00, 18.0, Parcela::Variable { factor: 1.4, reference: 1_668.89 }),
+		MarginalBracket::new(1_353.00, 18.0, Parcela::Fixed(116.65)),
+		MarginalBracket::new(2_127.00, 26.0, Parcela::Fixed(224.89)),
+		MarginalBracket::new(2_478.00, 32.75, Parcela::Fixed(368.46)),
+		MarginalBracket::new(3_210.00, 37.0, Parcela::Fixed(473.77)),
+		MarginalBracket::new(3_847.00, 38.72, Parcela::Fixed(528.98)),
+		MarginalBracket::new(5_028.00, 40.05, Parcela::Fixed(580.15)),
+		MarginalBracket::new(6_590.00, 41.72, Parcela::Fixed(664.12)),
+		MarginalBracket::new(7_880.00, 42.76, Parcela::Fixed(732.66)),
+		MarginalBracket::new(20_067.00, 44.05, Parcela::Fixed(834.31)),
+		MarginalBracket::new(f64::INFINITY, 47.17, Parcela::Fixed(1_460.40)),
+	];
+}
+
+/// Tables in force from January to June approximate 2022's, with the
+/// limits updated by the 5.1% of the year's IRS brackets. From July, marginal
+/// tables replaced them, and Tabela III, for married couples with a single
+/// holder, is approximated by Tabela I on 83% of the income.
+pub mod year_2023 {
+	use super::*;
+	pub const tables: [RetentionTables; 2] = [
+		RetentionTables {
+			from_month: 1,
+			nao_casado: &Table::Rates(&nao_casado),
+			casado_titular_unico: &Table::Rates(&casado_titular_unico),
+			casado_dois_titulares: &Table::Rates(&casado_dois_titulares),
+		},
+		RetentionTables {
+			from_month: 7,
+			nao_casado: &Table::Marginal { brackets: &brackets, per_dependent: 34.29 },
+			casado_titular_unico: &Table::Marginal { brackets: &titular_unico, per_dependent: 42.86 },
+			casado_dois_titulares: &Table::Marginal { brackets: &brackets, per_dependent: 21.43 },
+		},
+	];
+
+	pub const nao_casado: [(f64, [TaxRate; 6]); 35] = [
+		(762.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(778.00, [4.5, 0.6, 0.0, 0.0, 0.0, 0.0]),
+		(792.00, [6.3, 0.8, 0.0, 0.0, 0.0, 0.0]),
+		(856.00, [7.9, 4.5, 1.0, 0.0, 0.0, 0.0]),
+		(969.00, [10.1, 6.7, 3.5, 0.0, 0.0, 0.0]),
+		(1_056.00, [11.3, 7.9, 5.7, 1.4, 0.0, 0.0]),
+		(1_119.00, [12.1, 8.8, 6.5, 3.3, 0.0, 0.0]),
+		(1_201.00, [13.1, 10.7, 8.3, 5.1, 2.7, 0.2]),
+		(1_287.00, [14.1, 11.8, 9.3, 6.1, 3.6, 1.2]),
+		(1_388.00, [15.2, 12.8, 10.5, 7.0, 4.6, 2.2]),
+		(1_497.00, [16.2, 13.8, 11.4, 8.0, 6.5, 4.0]),
+		(1_642.00, [17.2, 14.8, 12.3, 10.0, 7.5, 5.0]),
+		(1_798.00, [18.6, 16.3, 14.8, 11.4, 8.9, 6.5]),
+		(1_965.00, [19.9, 18.2, 17.3, 14.5, 12.5, 11.7]),
+		(2_078.00, [20.9, 19.3, 18.2, 15.5, 14.5, 12.5]),
+		(2_197.00, [21.9, 20.2, 19.2, 16.4, 15.5, 13.5]),
+		(2_331.00, [22.8, 21.3, 20.3, 17.5, 16.5, 14.5]),
+		(2_488.00, [23.8, 22.2, 21.3, 18.5, 17.6, 15.5]),
+		(2_664.00, [24.8, 24.2, 22.2, 20.4, 18.5, 17.6]),
+		(2_908.00, [25.8, 25.1, 23.3, 21.4, 19.4, 18.5]),
+		(3_262.00, [27.0, 26.4, 24.5, 22.5, 20.6, 19.6]),
+		(3_714.00, [28.6, 28.3, 26.8, 25.2, 24.6, 23.0]),
+		(4_328.00, [29.7, 29.5, 27.7, 26.2, 25.6, 25.0]),
+		(4_887.00, [31.4, 31.0, 29.4, 27.6, 27.0, 26.5]),
+		(5_459.00, [32.3, 31.8, 31.3, 28.9, 28.0, 27.4]),
+		(6_180.00, [33.3, 32.8, 32.2, 29.8, 29.2, 28.4]),
+		(7_070.00, [35.3, 34.9, 34.1, 32.2, 31.8, 31.5]),
+		(8_344.00, [36.3, 35.9, 35.5, 34.2, 32.8, 32.4]),
+		(10_048.00, [38.2, 37.8, 37.4, 36.2, 35.8, 34.4]),
+		(11_857.00, [39.2, 38.8, 38.4, 37.5, 36.7, 35.4]),
+		(19_816.00, [40.2, 39.8, 39.4, 38.5, 38.1, 36.4]),
+		(21_252.00, [41.2, 40.8, 40.4, 39.5, 39.1, 37.3]),
+		(23_909.00, [41.9, 41.7, 41.4, 40.5, 40.1, 38.5]),
+		(26_565.00, [42.9, 42.7, 42.3, 41.4, 41.1, 39.7]),
+		(f64::INFINITY, [43.8, 43.6, 43.3, 42.4, 42.0, 40.7]),
+	];
+
+	pub const casado_titular_unico: [(f64, [TaxRate; 6]); 36] = [
+		(762.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(778.00, [3.3, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(792.00, [3.3, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(834.00, [4.7, 0.9, 0.0, 0.0, 0.0, 0.0]),
+		(879.00, [5.6, 1.8, 0.9, 0.0, 0.0, 0.0]),
+		(931.00, [6.5, 3.7, 1.2, 0.0, 0.0, 0.0]),
+		(1_024.00, [7.3, 4.6, 2.9, 0.0, 0.0, 0.0]),
+		(1_136.00, [8.1, 5.5, 3.7, 1.0, 0.0, 0.0]),
+		(1_287.00, [9.2, 6.9, 4.7, 2.0, 0.0, 0.0]),
+		(1_476.00, [10.7, 8.9, 7.1, 4.3, 2.6, 1.7]),
+		(1_712.00, [11.7, 10.0, 8.1, 6.3, 4.5, 2.7]),
+		(1_821.00, [13.1, 11.4, 10.6, 7.7, 5.9, 5.1]),
+		(1_943.00, [14.0, 12.4, 11.7, 9.0, 7.3, 6.5]),
+		(2_100.00, [15.0, 13.3, 12.5, 10.0, 9.2, 7.4]),
+		(2_267.00, [16.0, 14.3, 13.5, 10.9, 10.2, 8.5]),
+		(2_467.00, [17.0, 16.3, 14.6, 11.9, 11.1, 9.5]),
+		(2_697.00, [17.8, 17.2, 15.6, 13.8, 12.1, 11.4]),
+		(3_084.00, [18.9, 18.2, 16.6, 14.8, 13.1, 12.3]),
+		(3_527.00, [21.5, 21.4, 19.8, 18.4, 17.1, 16.7]),
+		(3_795.00, [22.4, 22.3, 21.0, 19.4, 19.0, 17.6]),
+		(4_080.00, [23.4, 23.3, 22.0, 20.6, 20.0, 18.6]),
+		(4_425.00, [24.4, 24.3, 22.9, 21.6, 21.2, 20.6]),
+		(4_839.00, [25.9, 25.3, 23.9, 22.5, 22.1, 21.8]),
+		(5_335.00, [26.9, 26.3, 25.9, 23.5, 23.1, 22.7]),
+		(5_942.00, [27.8, 27.2, 26.9, 24.5, 24.1, 23.7]),
+		(6_706.00, [28.8, 28.2, 27.8, 25.5, 25.1, 24.7]),
+		(7_696.00, [29.7, 29.6, 29.2, 27.0, 26.9, 26.7]),
+		(8_871.00, [30.7, 30.6, 30.4, 29.0, 27.8, 27.6]),
+		(9_812.00, [32.1, 32.0, 31.9, 30.7, 29.3, 29.1]),
+		(10_981.00, [33.1, 33.0, 32.8, 31.7, 31.5, 30.0]),
+		(14_728.00, [34.4, 34.3, 33.8, 32.6, 32.4, 31.3]),
+		(21_144.00, [36.4, 36.3, 36.2, 35.1, 34.9, 33.7]),
+		(23_909.00, [37.3, 37.2, 37.1, 36.5, 35.9, 34.7]),
+		(26_565.00, [38.3, 38.2, 38.1, 37.4, 37.2, 35.7]),
+		(29_753.00, [39.3, 39.2, 39.1, 38.4, 38.2, 37.0]),
+		(f64::INFINITY, [40.3, 40.2, 40.1, 39.4, 39.2, 38.0]),
+	];
+
+	pub const casado_dois_titulares: [(f64, [TaxRate; 6]); 35] = [
+		(762.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(778.00, [4.5, 3.4, 0.5, 0.0, 0.0, 0.0]),
+		(792.00, [6.3, 3.7, 1.0, 0.0, 0.0, 0.0]),
+		(856.00, [7.9, 5.1, 3.4, 2.5, 0.6, 0.0]),
+		(969.00, [10.1, 7.3, 6.5, 3.8, 3.1, 1.2]),
+		(1_056.00, [11.3, 8.6, 7.8, 5.1, 4.4, 3.1]),
+		(1_119.00, [12.1, 9.5, 8.6, 6.0, 4.8, 3.9]),
+		(1_201.00, [13.1, 11.4, 10.6, 7.9, 7.1, 5.3]),
+		(1_287.00, [14.1, 12.4, 11.5, 8.9, 8.0, 6.3]),
+		(1_388.00, [15.1, 14.4, 12.6, 10.7, 9.0, 8.1]),
+		(1_497.00, [16.1, 15.3, 13.6, 11.9, 10.0, 9.2]),
+		(1_642.00, [17.1, 16.4, 14.6, 12.8, 11.1, 10.2]),
+		(1_798.00, [18.5, 17.7, 16.1, 14.3, 13.4, 11.7]),
+		(1_965.00, [19.9, 19.3, 17.6, 16.0, 15.2, 13.5]),
+		(2_078.00, [20.9, 20.4, 18.5, 16.9, 16.1, 14.5]),
+		(2_197.00, [21.9, 21.4, 19.6, 17.7, 17.0, 16.3]),
+		(2_331.00, [22.8, 22.3, 20.7, 18.9, 17.9, 17.3]),
+		(2_488.00, [23.8, 23.4, 22.6, 19.9, 19.1, 18.2]),
+		(2_664.00, [24.8, 24.4, 23.6, 21.0, 20.2, 19.4]),
+		(2_908.00, [25.7, 25.2, 24.6, 21.9, 21.2, 20.4]),
+		(3_262.00, [26.9, 26.5, 25.7, 23.1, 22.3, 21.6]),
+		(3_714.00, [28.5, 28.4, 28.0, 25.7, 25.3, 24.9]),
+		(4_328.00, [29.6, 29.5, 29.0, 27.6, 26.3, 25.9]),
+		(4_887.00, [31.4, 31.1, 30.7, 29.0, 27.7, 27.3]),
+		(5_459.00, [32.3, 32.0, 31.6, 30.3, 29.6, 28.3]),
+		(6_180.00, [33.3, 33.0, 32.6, 31.3, 30.9, 29.2]),
+		(7_070.00, [35.2, 35.0, 34.5, 33.8, 33.6, 33.4]),
+		(8_344.00, [36.2, 36.0, 35.8, 34.7, 34.6, 34.4]),
+		(10_048.00, [38.1, 37.9, 37.7, 36.6, 36.4, 36.3]),
+		(11_857.00, [39.1, 38.9, 38.7, 38.0, 37.4, 37.2]),
+		(19_816.00, [40.1, 39.9, 39.7, 39.0, 38.8, 38.2]),
+		(21_252.00, [41.1, 40.9, 40.7, 40.0, 39.8, 39.2]),
+		(23_909.00, [41.8, 41.7, 41.6, 41.0, 40.8, 40.4]),
+		(26_565.00, [42.8, 42.7, 42.6, 41.9, 41.7, 41.5]),
+		(f64::INFINITY, [43.8, 43.7, 43.6, 42.9, 42.7, 42.5]),
+	];
+
+	pub const brackets: [MarginalBracket; 14] = [
+		MarginalBracket::new(762.00, 0.0, Parcela::Fixed(0.0)),
+		MarginalBracket::new(924.00, 14.5, Parcela::Variable { factor: 2.6, reference: 1_055.08 }),
+		MarginalBracket::new(1_050.00, 21.0, Parcela::Variable { factor: 1.35, reference: 1_310.16 }),
+		MarginalBracket::new(1_356.00, 21.0, Parcela::Fixed(73.76)),
+		MarginalBracket::new(1_472.00, 26.5, Parcela::Fixed(148.34)),
+		MarginalBracket::new(1_707.00, 28.5, Parcela::Fixed(177.78)),
+		MarginalBracket::new(2_068.00, 35.0, Parcela::Fixed(288.74)),
+		MarginalBracket::new(2_722.00, 37.0, Parcela::Fixed(330.10)),
+		MarginalBracket::new(3_216.00, 38.72, Parcela::Fixed(376.92)),
+		MarginalBracket::new(4_185.00, 40.05, Parcela::Fixed(419.69)),
+		MarginalBracket::new(5_477.00, 41.72, Parcela::Fixed(489.58)),
+		MarginalBracket::new(6_524.00, 42.77, Parcela::Fixed(547.09)),
+		MarginalBracket::new(20_067.00, 44.95, Parcela::Fixed(689.31)),
+		MarginalBracket::new(f64::INFINITY, 47.17, Parcela::Fixed(1_134.80)),
+	];
+
+	pub const titular_unico: [MarginalBracket; 14] = [
+		MarginalBracket::new(918.00, 0.0, Parcela::Fixed(0.0)),
+		MarginalBracket::new(1_113.00, 14.5, Parcela::Variable { factor: 2.6, reference: 1_271.08 }),
+		MarginalBracket::new(1_265.00, 21.0, Parcela::Variable { factor: 1.35, reference: 1_578.40 }),
+		MarginalBracket::new(1_634.00, 21.0, Parcela::Fixed(88.85)),
+		MarginalBracket::new(1_773.00, 26.5, Parcela::Fixed(178.72)),
+		MarginalBracket::new(2_057.00, 28.5, Parcela::Fixed(214.18)),
+		MarginalBracket::new(2_492.00, 35.0, Parcela::Fixed(347.88)),
+		MarginalBracket::new(3_280.00, 37.0, Parcela::Fixed(397.72)),
+		MarginalBracket::new(3_875.00, 38.72, Parcela::Fixed(454.14)),
+		MarginalBracket::new(5_042.00, 40.05, Parcela::Fixed(505.68)),
+		MarginalBracket::new(6_599.00, 41.72, Parcela::Fixed(589.88)),
+		MarginalBracket::new(7_860.00, 42.77, Parcela::Fixed(659.17)),
+		MarginalBracket::new(20_067.00, 44.95, Parcela::Fixed(830.52)),
+		MarginalBracket::new(f64::INFINITY, 47.17, Parcela::Fixed(1_276.01)),
+	];
+}
+
 pub mod year_2022 {
 	use super::*;
-	pub const tables: RetentionTables = RetentionTables {
-		nao_casado: &nao_casado,
-		casado_titular_unico: &casado_titular_unico,
-		casado_dois_titulares: &casado_dois_titulares,
-	};
+	pub const tables: [RetentionTables; 1] = [RetentionTables {
+		from_month: 1,
+		nao_casado: &Table::Rates(&nao_casado),
+		casado_titular_unico: &Table::Rates(&casado_titular_unico),
+		casado_dois_titulares: &Table::Rates(&casado_dois_titulares),
+	}];
 
-	pub fn tax(gross_monthly: Money, family: &FamilyElement) -> TaxRate { tables.tax(gross_monthly, family) }
+	pub fn tax(gross_monthly: Money, family: &FamilyElement) -> Result<TaxRate> {
+		tables[0].tax(gross_monthly, family)
+	}
 
 	pub const nao_casado: [(f64, [TaxRate; 6]); 36] = [
 		(710.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
@@ -79,7 +452,7 @@ pub mod year_2022 {
 		(20_221.00, [41.2, 40.8, 40.4, 39.5, 39.1, 37.3]),
 		(22_749.00, [41.9, 41.7, 41.4, 40.5, 40.1, 38.5]),
 		(25_276.00, [42.9, 42.7, 42.3, 41.4, 41.1, 39.7]),
-		(std::f64::INFINITY, [43.8, 43.6, 43.3, 42.4, 42.0, 40.7]),
+		(f64::INFINITY, [43.8, 43.6, 43.3, 42.4, 42.0, 40.7]),
 	];
 
 	pub const casado_titular_unico: [(f64, [TaxRate; 6]); 36] = [
@@ -118,7 +491,7 @@ pub mod year_2022 {
 		(22_749.00, [37.3, 37.2, 37.1, 36.5, 35.9, 34.7]),
 		(25_276.00, [38.3, 38.2, 38.1, 37.4, 37.2, 35.7]),
 		(28_309.00, [39.3, 39.2, 39.1, 38.4, 38.2, 37.0]),
-		(std::f64::INFINITY, [40.3, 40.2, 40.1, 39.4, 39.2, 38.0]),
+		(f64::INFINITY, [40.3, 40.2, 40.1, 39.4, 39.2, 38.0]),
 	];
 
 	pub const casado_dois_titulares: [(f64, [TaxRate; 6]); 36] = [
@@ -157,13 +530,21 @@ pub mod year_2022 {
 		(20_221.00, [41.1, 40.9, 40.7, 40.0, 39.8, 39.2]),
 		(22_749.00, [41.8, 41.7, 41.6, 41.0, 40.8, 40.4]),
 		(25_276.00, [42.8, 42.7, 42.6, 41.9, 41.7, 41.5]),
-		(std::f64::INFINITY, [43.8, 43.7, 43.6, 42.9, 42.7, 42.5]),
+		(f64::INFINITY, [43.8, 43.7, 43.6, 42.9, 42.7, 42.5]),
 	];
 }
 
-#[allow(dead_code)]
-mod ano_2021 {
+/// The tables for married taxpayers are approximated from the one for
+/// unmarried taxpayers, with the differences between them in 2022.
+pub mod year_2021 {
 	use super::*;
+	pub const tables: [RetentionTables; 1] = [RetentionTables {
+		from_month: 1,
+		nao_casado: &Table::Rates(&dependente_nao_casado),
+		casado_titular_unico: &Table::Rates(&casado_titular_unico),
+		casado_dois_titulares: &Table::Rates(&casado_dois_titulares),
+	}];
+
 	pub const dependente_nao_casado: [(f64, [TaxRate; 6]); 35] = [
 		(686.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
 		(718.00, [4.0, 0.7, 0.0, 0.0, 0.0, 0.0]),
@@ -199,13 +580,98 @@ mod ano_2021 {
 		(20_221.00, [41.5, 41.1, 40.7, 39.8, 39.4, 37.6]),
 		(22_749.00, [42.2, 42.0, 41.7, 40.8, 40.4, 38.8]),
 		(25_276.00, [43.2, 43.0, 42.6, 41.7, 41.4, 40.0]),
-		(std::f64::INFINITY, [44.2, 44.0, 43.6, 42.7, 42.3, 41.0]),
+		(f64::INFINITY, [44.2, 44.0, 43.6, 42.7, 42.3, 41.0]),
+	];
+
+	pub const casado_titular_unico: [(f64, [TaxRate; 6]); 36] = [
+		(686.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(740.00, [3.3, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(754.00, [3.3, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(794.00, [4.7, 0.9, 0.0, 0.0, 0.0, 0.0]),
+		(836.00, [5.6, 1.8, 0.9, 0.0, 0.0, 0.0]),
+		(886.00, [6.5, 3.7, 1.2, 0.0, 0.0, 0.0]),
+		(974.00, [7.3, 4.6, 2.9, 0.0, 0.0, 0.0]),
+		(1_081.00, [8.1, 5.5, 3.7, 1.0, 0.0, 0.0]),
+		(1_225.00, [9.3, 7.0, 4.8, 2.0, 0.0, 0.0]),
+		(1_404.00, [10.8, 9.0, 7.2, 4.4, 2.7, 1.7]),
+		(1_629.00, [11.8, 10.1, 8.2, 6.4, 4.6, 2.8]),
+		(1_733.00, [13.3, 11.5, 10.7, 7.8, 6.0, 5.2]),
+		(1_849.00, [14.2, 12.5, 11.8, 9.1, 7.4, 6.6]),
+		(1_998.00, [15.2, 13.5, 12.7, 10.1, 9.3, 7.5]),
+		(2_157.00, [16.2, 14.5, 13.7, 11.0, 10.3, 8.6]),
+		(2_347.00, [17.2, 16.5, 14.8, 12.0, 11.2, 9.6]),
+		(2_566.00, [18.0, 17.4, 15.8, 14.0, 12.3, 11.5]),
+		(2_934.00, [19.1, 18.4, 16.8, 15.0, 13.3, 12.5]),
+		(3_356.00, [21.7, 21.6, 20.0, 18.6, 17.3, 16.9]),
+		(3_611.00, [22.6, 22.5, 21.2, 19.6, 19.2, 17.8]),
+		(3_882.00, [23.6, 23.5, 22.2, 20.8, 20.2, 18.8]),
+		(4_210.00, [24.7, 24.5, 23.1, 21.8, 21.4, 20.8]),
+		(4_604.00, [26.2, 25.5, 24.1, 22.7, 22.3, 22.0]),
+		(5_076.00, [27.2, 26.6, 26.2, 23.7, 23.3, 22.9]),
+		(5_654.00, [28.1, 27.5, 27.2, 24.7, 24.3, 23.9]),
+		(6_381.00, [29.1, 28.5, 28.1, 25.8, 25.4, 25.0]),
+		(7_323.00, [30.0, 29.9, 29.5, 27.3, 27.2, 27.0]),
+		(8_441.00, [31.0, 30.9, 30.7, 29.3, 28.1, 27.9]),
+		(9_336.00, [32.4, 32.3, 32.2, 31.0, 29.6, 29.4]),
+		(10_448.00, [33.4, 33.3, 33.1, 32.0, 31.8, 30.3]),
+		(14_013.00, [34.7, 34.6, 34.1, 32.9, 32.7, 31.6]),
+		(20_118.00, [36.7, 36.6, 36.5, 35.4, 35.2, 34.0]),
+		(22_749.00, [37.6, 37.5, 37.4, 36.8, 36.2, 35.0]),
+		(25_276.00, [38.6, 38.5, 38.4, 37.7, 37.5, 36.0]),
+		(28_309.00, [39.7, 39.6, 39.4, 38.7, 38.5, 37.3]),
+		(f64::INFINITY, [40.7, 40.6, 40.4, 39.7, 39.5, 38.3]),
+	];
+
+	pub const casado_dois_titulares: [(f64, [TaxRate; 6]); 35] = [
+		(686.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(718.00, [4.0, 3.5, 0.5, 0.0, 0.0, 0.0]),
+		(739.00, [7.2, 5.6, 1.0, 0.0, 0.0, 0.0]),
+		(814.00, [8.0, 5.6, 3.4, 2.5, 0.6, 0.0]),
+		(922.00, [10.2, 7.4, 6.5, 3.8, 3.1, 1.2]),
+		(1_005.00, [11.4, 8.7, 7.8, 5.1, 4.4, 3.1]),
+		(1_065.00, [12.2, 9.6, 8.7, 6.0, 4.8, 3.9]),
+		(1_143.00, [13.2, 11.5, 10.7, 7.9, 7.1, 5.3]),
+		(1_225.00, [14.2, 12.5, 11.6, 8.9, 8.0, 6.3]),
+		(1_321.00, [15.2, 14.5, 12.7, 10.8, 9.0, 8.1]),
+		(1_424.00, [16.2, 15.4, 13.7, 12.0, 10.1, 9.2]),
+		(1_562.00, [17.2, 16.5, 14.7, 12.9, 11.2, 10.2]),
+		(1_711.00, [18.6, 17.8, 16.2, 14.4, 13.5, 11.8]),
+		(1_870.00, [20.1, 19.4, 17.7, 16.1, 15.3, 13.6]),
+		(1_977.00, [21.1, 20.6, 18.6, 17.0, 16.2, 14.6]),
+		(2_090.00, [22.1, 21.6, 19.8, 17.8, 17.1, 16.4]),
+		(2_218.00, [23.0, 22.5, 20.9, 19.0, 18.0, 17.4]),
+		(2_367.00, [24.0, 23.6, 22.8, 20.0, 19.2, 18.3]),
+		(2_535.00, [25.0, 24.6, 23.8, 21.2, 20.3, 19.5]),
+		(2_767.00, [25.9, 25.4, 24.8, 22.1, 21.4, 20.5]),
+		(3_104.00, [27.1, 26.7, 25.9, 23.3, 22.5, 21.8]),
+		(3_534.00, [28.7, 28.6, 28.2, 25.9, 25.5, 25.1]),
+		(4_118.00, [29.8, 29.7, 29.2, 27.8, 26.5, 26.1]),
+		(4_650.00, [31.7, 31.3, 30.9, 29.2, 27.9, 27.5]),
+		(5_194.00, [32.6, 32.3, 31.9, 30.5, 29.8, 28.5]),
+		(5_880.00, [33.6, 33.3, 32.9, 31.5, 31.1, 29.4]),
+		(6_727.00, [35.5, 35.3, 34.8, 34.1, 33.9, 33.7]),
+		(7_939.00, [36.5, 36.3, 36.1, 35.0, 34.9, 34.7]),
+		(9_560.00, [38.4, 38.2, 38.0, 36.9, 36.7, 36.6]),
+		(11_282.00, [39.4, 39.2, 39.0, 38.3, 37.7, 37.5]),
+		(18_854.00, [40.4, 40.2, 40.0, 39.3, 39.1, 38.5]),
+		(20_221.00, [41.4, 41.2, 41.0, 40.3, 40.1, 39.5]),
+		(22_749.00, [42.1, 42.0, 41.9, 41.3, 41.1, 40.7]),
+		(25_276.00, [43.1, 43.0, 42.9, 42.2, 42.0, 41.8]),
+		(f64::INFINITY, [44.2, 44.1, 43.9, 43.2, 43.0, 42.8]),
 	];
 }
 
-#[allow(dead_code)]
-mod ano_2020 {
+/// The tables for married taxpayers are approximated from the one for
+/// unmarried taxpayers, with the differences between them in 2022.
+pub mod year_2020 {
 	use super::*;
+	pub const tables: [RetentionTables; 1] = [RetentionTables {
+		from_month: 1,
+		nao_casado: &Table::Rates(&dependente_nao_casado),
+		casado_titular_unico: &Table::Rates(&casado_titular_unico),
+		casado_dois_titulares: &Table::Rates(&casado_dois_titulares),
+	}];
+
 	pub const dependente_nao_casado: [(f64, [TaxRate; 6]); 36] = [
 		(659.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
 		(686.00, [0.1, 0.0, 0.0, 0.0, 0.0, 0.0]),
@@ -242,13 +708,99 @@ mod ano_2020 {
 		(20_221.00, [42.3, 41.9, 41.5, 40.6, 40.2, 38.4]),
 		(22_749.00, [43.1, 42.9, 42.5, 41.6, 41.2, 39.6]),
 		(25_276.00, [44.1, 43.9, 43.5, 42.6, 42.2, 40.8]),
-		(std::f64::INFINITY, [45.1, 44.9, 44.5, 43.6, 43.2, 41.8]),
+		(f64::INFINITY, [45.1, 44.9, 44.5, 43.6, 43.2, 41.8]),
+	];
+
+	pub const casado_titular_unico: [(f64, [TaxRate; 6]); 36] = [
+		(659.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(740.00, [3.3, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(754.00, [3.3, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(794.00, [4.7, 0.9, 0.0, 0.0, 0.0, 0.0]),
+		(836.00, [5.6, 1.8, 0.9, 0.0, 0.0, 0.0]),
+		(886.00, [6.5, 3.7, 1.2, 0.0, 0.0, 0.0]),
+		(974.00, [7.3, 4.6, 2.9, 0.0, 0.0, 0.0]),
+		(1_081.00, [8.1, 5.5, 3.7, 1.0, 0.0, 0.0]),
+		(1_225.00, [9.6, 7.2, 5.0, 2.1, 0.0, 0.0]),
+		(1_404.00, [11.1, 9.3, 7.4, 4.6, 2.8, 1.8]),
+		(1_629.00, [12.2, 10.4, 8.5, 6.6, 4.8, 2.9]),
+		(1_733.00, [13.7, 11.9, 11.1, 8.1, 6.3, 5.4]),
+		(1_849.00, [14.6, 12.9, 12.2, 9.4, 7.7, 6.8]),
+		(1_998.00, [15.6, 13.9, 13.1, 10.4, 9.6, 7.8]),
+		(2_157.00, [16.7, 14.9, 14.1, 11.4, 10.6, 8.9]),
+		(2_347.00, [17.7, 17.0, 15.2, 12.4, 11.6, 9.9]),
+		(2_566.00, [18.5, 17.9, 16.3, 14.4, 12.7, 11.9]),
+		(2_934.00, [19.7, 18.9, 17.3, 15.5, 13.7, 12.9]),
+		(3_356.00, [22.3, 22.2, 20.5, 19.1, 17.8, 17.4]),
+		(3_611.00, [23.2, 23.1, 21.8, 20.1, 19.7, 18.3]),
+		(3_882.00, [24.2, 24.1, 22.8, 21.3, 20.7, 19.3]),
+		(4_210.00, [25.3, 25.1, 23.7, 22.4, 22.0, 21.3]),
+		(4_604.00, [26.8, 26.1, 24.7, 23.3, 22.9, 22.5]),
+		(5_076.00, [27.9, 27.3, 26.8, 24.3, 23.9, 23.5]),
+		(5_654.00, [28.8, 28.2, 27.9, 25.3, 24.9, 24.5]),
+		(6_381.00, [29.8, 29.2, 28.8, 26.5, 26.1, 25.6]),
+		(7_323.00, [30.7, 30.6, 30.2, 28.0, 27.9, 27.7]),
+		(8_441.00, [31.8, 31.7, 31.5, 30.0, 28.8, 28.6]),
+		(9_336.00, [33.2, 33.1, 33.0, 31.7, 30.3, 30.1]),
+		(10_448.00, [34.2, 34.1, 33.9, 32.8, 32.6, 31.0]),
+		(14_013.00, [35.5, 35.4, 34.9, 33.7, 33.5, 32.3]),
+		(20_118.00, [37.5, 37.4, 37.3, 36.2, 36.0, 34.8]),
+		(22_749.00, [38.5, 38.4, 38.2, 37.6, 37.0, 35.8]),
+		(25_276.00, [39.5, 39.4, 39.3, 38.6, 38.3, 36.8]),
+		(28_309.00, [40.6, 40.5, 40.3, 39.6, 39.4, 38.1]),
+		(f64::INFINITY, [41.6, 41.5, 41.3, 40.6, 40.4, 39.1]),
+	];
+
+	pub const casado_dois_titulares: [(f64, [TaxRate; 6]); 36] = [
+		(659.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(686.00, [0.1, 0.1, 0.0, 0.0, 0.0, 0.0]),
+		(718.00, [4.2, 3.6, 0.5, 0.0, 0.0, 0.0]),
+		(739.00, [7.3, 5.7, 1.2, 0.0, 0.0, 0.0]),
+		(814.00, [8.2, 5.7, 3.5, 2.5, 0.6, 0.0]),
+		(922.00, [10.4, 7.5, 6.6, 3.8, 3.1, 1.2]),
+		(1_005.00, [11.6, 8.9, 7.9, 5.2, 4.4, 3.1]),
+		(1_065.00, [12.4, 9.8, 8.8, 6.1, 4.8, 3.9]),
+		(1_143.00, [13.5, 11.7, 10.9, 8.0, 7.2, 5.4]),
+		(1_225.00, [14.5, 12.7, 11.8, 9.0, 8.1, 6.4]),
+		(1_321.00, [15.5, 14.8, 12.9, 10.9, 9.1, 8.2]),
+		(1_424.00, [16.5, 15.7, 13.9, 12.2, 10.2, 9.3]),
+		(1_562.00, [17.6, 16.8, 15.0, 13.1, 11.4, 10.3]),
+		(1_711.00, [19.0, 18.1, 16.5, 14.6, 13.7, 11.9]),
+		(1_870.00, [20.5, 19.8, 18.1, 16.4, 15.6, 13.8]),
+		(1_977.00, [21.5, 21.0, 19.0, 17.3, 16.5, 14.9]),
+		(2_090.00, [22.5, 22.0, 20.2, 18.1, 17.4, 16.7]),
+		(2_218.00, [23.5, 22.9, 21.3, 19.4, 18.3, 17.7]),
+		(2_367.00, [24.5, 24.1, 23.2, 20.4, 19.6, 18.6]),
+		(2_535.00, [25.5, 25.1, 24.3, 21.6, 20.7, 19.9]),
+		(2_767.00, [26.4, 25.9, 25.3, 22.5, 21.8, 20.9]),
+		(3_104.00, [27.7, 27.2, 26.4, 23.8, 22.9, 22.2]),
+		(3_534.00, [29.3, 29.2, 28.7, 26.4, 26.0, 25.6]),
+		(4_118.00, [30.4, 30.3, 29.8, 28.3, 27.0, 26.6]),
+		(4_650.00, [32.3, 31.9, 31.5, 29.8, 28.5, 28.0]),
+		(5_194.00, [33.3, 33.0, 32.5, 31.1, 30.4, 29.1]),
+		(5_880.00, [34.3, 34.0, 33.6, 32.1, 31.7, 30.0]),
+		(6_727.00, [36.2, 36.0, 35.5, 34.8, 34.6, 34.3]),
+		(7_939.00, [37.2, 37.0, 36.8, 35.7, 35.6, 35.4]),
+		(9_560.00, [39.2, 39.0, 38.8, 37.6, 37.4, 37.3]),
+		(11_282.00, [40.2, 40.0, 39.8, 39.1, 38.5, 38.2]),
+		(18_854.00, [41.2, 41.0, 40.8, 40.1, 39.9, 39.2]),
+		(20_221.00, [42.2, 42.0, 41.8, 41.1, 40.9, 40.3]),
+		(22_749.00, [43.0, 42.9, 42.7, 42.1, 41.9, 41.5]),
+		(25_276.00, [44.0, 43.9, 43.8, 43.1, 42.8, 42.6]),
+		(f64::INFINITY, [45.1, 45.0, 44.8, 44.1, 43.9, 43.6]),
 	];
 }
 
-#[allow(dead_code)]
-mod ano_2019 {
+/// The tables for married taxpayers are approximated from the one for
+/// unmarried taxpayers, with the differences between them in 2022.
+pub mod year_2019 {
 	use super::*;
+	pub const tables: [RetentionTables; 1] = [RetentionTables {
+		from_month: 1,
+		nao_casado: &Table::Rates(&dependente_nao_casado),
+		casado_titular_unico: &Table::Rates(&casado_titular_unico),
+		casado_dois_titulares: &Table::Rates(&casado_dois_titulares),
+	}];
+
 	pub const dependente_nao_casado: [(f64, [TaxRate; 6]); 36] = [
 		(654.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
 		(683.00, [0.2, 0.0, 0.0, 0.0, 0.0, 0.0]),
@@ -285,6 +837,148 @@ mod ano_2019 {
 		(20160.00, [42.5, 42.1, 41.7, 40.8, 40.4, 38.6]),
 		(22680.00, [43.3, 43.1, 42.7, 41.8, 41.4, 39.8]),
 		(25200.00, [44.3, 44.1, 43.7, 42.8, 42.4, 41.0]),
-		(std::f64::INFINITY, [45.3, 45.1, 44.7, 43.8, 43.4, 42.0]),
+		(f64::INFINITY, [45.3, 45.1, 44.7, 43.8, 43.4, 42.0]),
+	];
+
+	pub const casado_titular_unico: [(f64, [TaxRate; 6]); 36] = [
+		(654.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(740.00, [3.3, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(754.00, [3.3, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(794.00, [4.7, 0.9, 0.0, 0.0, 0.0, 0.0]),
+		(836.00, [5.6, 1.8, 0.9, 0.0, 0.0, 0.0]),
+		(886.00, [6.5, 3.7, 1.2, 0.0, 0.0, 0.0]),
+		(974.00, [7.3, 4.6, 2.9, 0.0, 0.0, 0.0]),
+		(1_081.00, [8.1, 5.5, 3.7, 1.0, 0.0, 0.0]),
+		(1_225.00, [9.7, 7.3, 5.1, 2.2, 0.0, 0.0]),
+		(1_404.00, [11.2, 9.4, 7.5, 4.7, 2.9, 1.9]),
+		(1_629.00, [12.3, 10.5, 8.6, 6.7, 4.9, 3.0]),
+		(1_733.00, [13.8, 12.0, 11.2, 8.2, 6.4, 5.5]),
+		(1_849.00, [14.7, 13.0, 12.3, 9.5, 7.8, 6.9]),
+		(1_998.00, [15.7, 14.0, 13.2, 10.5, 9.7, 7.9]),
+		(2_157.00, [16.8, 15.0, 14.2, 11.5, 10.7, 9.0]),
+		(2_347.00, [17.8, 17.1, 15.3, 12.5, 11.7, 10.0]),
+		(2_566.00, [18.6, 18.0, 16.4, 14.5, 12.8, 12.0]),
+		(2_934.00, [19.8, 19.0, 17.4, 15.6, 13.8, 13.0]),
+		(3_356.00, [22.4, 22.3, 20.6, 19.2, 17.9, 17.5]),
+		(3_611.00, [23.4, 23.3, 21.9, 20.2, 19.8, 18.4]),
+		(3_882.00, [24.4, 24.3, 22.9, 21.4, 20.8, 19.4]),
+		(4_210.00, [25.5, 25.3, 23.9, 22.5, 22.1, 21.4]),
+		(4_604.00, [27.0, 26.3, 24.9, 23.4, 23.0, 22.6]),
+		(5_076.00, [28.1, 27.5, 27.0, 24.4, 24.0, 23.6]),
+		(5_654.00, [29.0, 28.4, 28.1, 25.5, 25.1, 24.6]),
+		(6_381.00, [30.0, 29.4, 29.0, 26.7, 26.3, 25.8]),
+		(7_323.00, [30.9, 30.8, 30.4, 28.2, 28.1, 27.9]),
+		(8_441.00, [32.0, 31.9, 31.7, 30.2, 29.0, 28.8]),
+		(9_336.00, [33.4, 33.3, 33.2, 31.9, 30.5, 30.3]),
+		(10_448.00, [34.4, 34.3, 34.1, 33.0, 32.8, 31.2]),
+		(14_013.00, [35.7, 35.6, 35.1, 33.9, 33.7, 32.5]),
+		(20_118.00, [37.7, 37.6, 37.5, 36.4, 36.2, 35.0]),
+		(22_749.00, [38.7, 38.6, 38.4, 37.8, 37.2, 36.0]),
+		(25_276.00, [39.7, 39.6, 39.5, 38.8, 38.5, 37.0]),
+		(28_309.00, [40.8, 40.7, 40.5, 39.8, 39.6, 38.3]),
+		(f64::INFINITY, [41.8, 41.7, 41.5, 40.8, 40.6, 39.3]),
 	];
+
+	pub const casado_dois_titulares: [(f64, [TaxRate; 6]); 36] = [
+		(654.00, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0]),
+		(683.00, [0.2, 0.2, 0.0, 0.0, 0.0, 0.0]),
+		(715.00, [4.4, 3.8, 0.5, 0.0, 0.0, 0.0]),
+		(736.00, [7.4, 5.8, 1.3, 0.0, 0.0, 0.0]),
+		(811.00, [8.3, 5.8, 3.6, 2.5, 0.6, 0.0]),
+		(919.00, [10.5, 7.6, 6.7, 3.9, 3.1, 1.2]),
+		(1_001.00, [11.7, 9.0, 8.0, 5.3, 4.4, 3.1]),
+		(1_061.00, [12.5, 9.9, 8.9, 6.2, 4.8, 3.9]),
+		(1_139.00, [13.6, 11.8, 11.0, 8.1, 7.3, 5.5]),
+		(1_221.00, [14.6, 12.8, 11.9, 9.1, 8.2, 6.5]),
+		(1_317.00, [15.6, 14.9, 13.0, 11.0, 9.2, 8.3]),
+		(1_419.00, [16.6, 15.8, 14.0, 12.3, 10.3, 9.4]),
+		(1_557.00, [17.7, 16.9, 15.1, 13.2, 11.5, 10.4]),
+		(1_705.00, [19.1, 18.2, 16.6, 14.7, 13.8, 12.0]),
+		(1_864.00, [20.6, 19.9, 18.2, 16.5, 15.7, 13.9]),
+		(1_971.00, [21.6, 21.1, 19.1, 17.4, 16.6, 15.0]),
+		(2_083.00, [22.6, 22.1, 20.3, 18.2, 17.5, 16.8]),
+		(2_211.00, [23.6, 23.0, 21.4, 19.5, 18.4, 17.8]),
+		(2_359.00, [24.6, 24.2, 23.3, 20.5, 19.7, 18.7]),
+		(2_527.00, [25.6, 25.2, 24.4, 21.7, 20.8, 20.0]),
+		(2_758.00, [26.5, 26.0, 25.4, 22.6, 21.9, 21.0]),
+		(3_094.00, [27.8, 27.3, 26.5, 23.9, 23.0, 22.3]),
+		(3_523.00, [29.4, 29.3, 28.8, 26.5, 26.1, 25.7]),
+		(4_105.00, [30.6, 30.5, 29.9, 28.4, 27.1, 26.7]),
+		(4_636.00, [32.5, 32.1, 31.7, 29.9, 28.6, 28.1]),
+		(5_178.00, [33.5, 33.2, 32.7, 31.2, 30.5, 29.2]),
+		(5_862.00, [34.5, 34.2, 33.8, 32.3, 31.9, 30.1]),
+		(6_706.00, [36.4, 36.2, 35.7, 35.0, 34.8, 34.5]),
+		(7_915.00, [37.4, 37.2, 37.0, 35.9, 35.8, 35.6]),
+		(9_531.00, [39.4, 39.2, 39.0, 37.8, 37.6, 37.5]),
+		(11_248.00, [40.4, 40.2, 40.0, 39.3, 38.7, 38.4]),
+		(18_797.00, [41.4, 41.2, 41.0, 40.3, 40.1, 39.4]),
+		(20_160.00, [42.4, 42.2, 42.0, 41.3, 41.1, 40.5]),
+		(22_680.00, [43.2, 43.1, 42.9, 42.3, 42.1, 41.7]),
+		(25_200.00, [44.2, 44.1, 44.0, 43.3, 43.0, 42.8]),
+		(f64::INFINITY, [45.3, 45.2, 45.0, 44.3, 44.1, 43.8]),
+	];
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn marginal_tables() {
+		let tables = &year_2024::tables[0];
+		let single = FamilyElement::new(false, false, 0).unwrap();
+		let rate = |income: f64, family: &FamilyElement| tables.tax(Money::new(income), family).unwrap();
+		assert_eq!(rate(820.0, &single), 0.0);
+		// 1500€ × 26% − 186.66€
+		assert!((rate(1500.0, &single) * 1500.0 - 203.34).abs() < 1e-9);
+		let parent = FamilyElement::new(false, false, 1).unwrap();
+		assert!((rate(1500.0, &parent) * 1500.0 - (203.34 - 34.29)).abs() < 1e-9);
+		let couple = FamilyElement::new(true, false, 1).unwrap();
+		assert!((rate(1500.0, &couple) * 1500.0 - (203.34 - 21.43)).abs() < 1e-9);
+		let single_holder = FamilyElement::new(true, true, 0).unwrap();
+		assert!(rate(1500.0, &single_holder) < rate(1500.0, &single));
+		assert_eq!(rate(980.0, &single_holder), 0.0);
+		// Withholding doesn't jump from one bracket to the next
+		for brackets in [
+			&year_2023::brackets[..],
+			&year_2023::titular_unico,
+			&year_2024::brackets,
+			&year_2024::titular_unico,
+			&year_2025::brackets,
+			&year_2025::titular_unico,
+			&year_2026::brackets,
+			&year_2026::titular_unico,
+		] {
+			assert_eq!(brackets[0].withholding(brackets[0].limit), 0.0);
+			for pair in brackets.windows(2) {
+				let limit = pair[0].limit;
+				assert!((pair[0].withholding(limit) - pair[1].withholding(limit)).abs() < 0.01, "{}", limit);
+			}
+		}
+	}
+
+	#[test]
+	fn rate_tables() {
+		let tables = [
+			&year_2019::tables[0],
+			&year_2020::tables[0],
+			&year_2021::tables[0],
+			&year_2022::tables[0],
+			&year_2023::tables[0],
+		];
+		for tables in tables {
+			for (casado, titular_unico) in [(false, false), (true, true), (true, false)] {
+				let family = FamilyElement::new(casado, titular_unico, 0).unwrap();
+				let Table::Rates(rows) = tables.for_family(&family) else { panic!("{:?}", family) };
+				assert_eq!(rows[0].1, [0.0; 6]);
+				assert_eq!(rows.last().unwrap().0, f64::INFINITY);
+				// Rates grow with the income, and fall with more dependents
+				for pair in rows.windows(2) {
+					assert!(pair[0].0 < pair[1].0);
+					assert!((0..6).all(|i| pair[0].1[i] <= pair[1].1[i]), "{:?}", pair);
+				}
+				assert!(rows.iter().all(|row| row.1.windows(2).all(|rates| rates[0] >= rates[1])));
+			}
+		}
+	}
 }
@@ -0,0 +1,202 @@
+//! Tax residence in Portugal (CIRS, art. 16.º).
+
+use crate::{irs::EstatutoFiscal, units::Date, Error, Result};
+
+/// Days spent in Portugal in the 12 months from `start`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Presence {
+	pub start: Date,
+	pub days: u32,
+}
+
+impl Presence {
+	/// Last day of the 12 months.
+	pub fn end(&self) -> Date {
+		let (year, month, day) = (self.start.year() + 1, self.start.month(), self.start.day());
+		// 29 February is followed by 28 February
+		Date::new(year, month, day).unwrap_or_else(|_| Date::new(year, 3, 1).unwrap()).add_days(-1)
+	}
+}
+
+/// Where someone was during a year.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Whereabouts {
+	/// Days in Portugal in 12-month periods starting or ending in the year.
+	pub presence: Vec<Presence>,
+	/// First day with a home in Portugal kept as habitual residence.
+	pub habitual_home: Option<Date>,
+	/// Arrival in Portugal to stay.
+	pub arrival: Option<Date>,
+	/// Last day in Portugal before leaving to live abroad.
+	pub departure: Option<Date>,
+	/// Was resident, but not in the 3 years before arriving (Programa
+	/// Regressar, CIRS, art. 12.º-A).
+	pub returning: bool,
+}
+
+/// Estatuto fiscal between two days of a year, for the context of the
+/// salaries paid in them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ResidencePeriod {
+	pub first: Date,
+	pub last: Date,
+	pub estatuto_fiscal: EstatutoFiscal,
+}
+
+/// Estatuto fiscal over the days of `year`. Someone who, in any 12-month
+/// period starting or ending in the year, spends more than 183 days in
+/// Portugal, or has a home kept as habitual residence, is resident from the
+/// day of arrival and until the day of departure.
+pub fn residence(year: u32, whereabouts: &Whereabouts) -> Result<Vec<ResidencePeriod>> {
+	let (first_of_year, last_of_year) = (Date::first_of_year(year), Date::last_of_year(year));
+	if let (Some(arrival), Some(departure)) = (whereabouts.arrival, whereabouts.departure) {
+		if departure < arrival {
+			let message = format!("departure on {} before arriving on {}", departure, arrival);
+			return Err(Error::InvalidContext(message));
+		}
+	}
+	let periods: Vec<_> = whereabouts
+		.presence
+		.iter()
+		.filter(|presence| presence.start.year() == year || presence.end().year() == year)
+		.collect();
+	if let Some(presence) = periods.iter().find(|presence| presence.days > 366) {
+		return Err(Error::InvalidContext(format!(
+			"{} days in the 12 months from {}",
+			presence.days, presence.start
+		)));
+	}
+	let home = |presence: &&Presence| whereabouts.habitual_home.is_some_and(|home| home <= presence.end());
+	let resident = periods.iter().any(|presence| presence.days > 183 || home(presence))
+		|| (periods.is_empty() && whereabouts.habitual_home.is_some_and(|home| home <= last_of_year));
+	let resident_estatuto =
+		if whereabouts.returning { EstatutoFiscal::ExResidente } else { EstatutoFiscal::Residente };
+	let first = whereabouts.arrival.map_or(first_of_year, |arrival| arrival.max(first_of_year));
+	let last = whereabouts.departure.map_or(last_of_year, |departure| departure.min(last_of_year));
+	// Arriving after the year, or leaving before it, leaves no resident days
+	if !resident || first > last {
+		return Ok(vec![ResidencePeriod {
+			first: first_of_year,
+			last: last_of_year,
+			estatuto_fiscal: EstatutoFiscal::NãoResidente,
+		}]);
+	}
+	let parts = [
+		(first_of_year, first.add_days(-1), EstatutoFiscal::NãoResidente),
+		(first, last, resident_estatuto),
+		(last.add_days(1), last_of_year, EstatutoFiscal::NãoResidente),
+	];
+	Ok(parts
+		.into_iter()
+		.filter(|(first, last, _)| first <= last && first.year() == year && last.year() == year)
+		.map(|(first, last, estatuto_fiscal)| ResidencePeriod { first, last, estatuto_fiscal })
+		.collect())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn date(date: &str) -> Date { date.parse().unwrap() }
+
+	#[test]
+	fn days_present() {
+		let whereabouts = Whereabouts {
+			presence: vec![Presence { start: date("2022-01-01"), days: 200 }],
+			..Default::default()
+		};
+		let periods = residence(2022, &whereabouts).unwrap();
+		assert_eq!(periods.len(), 1);
+		assert_eq!(periods[0].estatuto_fiscal, EstatutoFiscal::Residente);
+		let whereabouts = Whereabouts {
+			presence: vec![Presence { start: date("2022-01-01"), days: 183 }],
+			..Default::default()
+		};
+		let periods = residence(2022, &whereabouts).unwrap();
+		assert_eq!(periods[0].estatuto_fiscal, EstatutoFiscal::NãoResidente);
+		// A habitual home is enough, even with fewer days
+		let whereabouts = Whereabouts { habitual_home: Some(date("2022-06-01")), ..whereabouts };
+		assert_eq!(residence(2022, &whereabouts).unwrap()[0].estatuto_fiscal, EstatutoFiscal::Residente);
+	}
+
+	#[test]
+	fn split_year() {
+		// Arrived in September and stayed past the 183 days of the 12 months
+		let whereabouts = Whereabouts {
+			presence: vec![Presence { start: date("2022-09-15"), days: 300 }],
+			arrival: Some(date("2022-09-15")),
+			returning: true,
+			..Default::default()
+		};
+		let periods = residence(2022, &whereabouts).unwrap();
+		assert_eq!(
+			periods,
+			[
+				ResidencePeriod {
+					first: date("2022-01-01"),
+					last: date("2022-09-14"),
+					estatuto_fiscal: EstatutoFiscal::NãoResidente,
+				},
+				ResidencePeriod {
+					first: date("2022-09-15"),
+					last: date("2022-12-31"),
+					estatuto_fiscal: EstatutoFiscal::ExResidente,
+				},
+			]
+		);
+		// Left in March
+		let whereabouts = Whereabouts {
+			presence: vec![Presence { start: date("2021-04-01"), days: 340 }],
+			departure: Some(date("2022-03-31")),
+			..Default::default()
+		};
+		let periods = residence(2022, &whereabouts).unwrap();
+		assert_eq!(periods[0].last, date("2022-03-31"));
+		assert_eq!(periods[1].estatuto_fiscal, EstatutoFiscal::NãoResidente);
+	}
+
+	#[test]
+	fn resident_days_outside_the_year() {
+		// The 12 months from October count for 2022, but the arrival is in 2023
+		let whereabouts = Whereabouts {
+			presence: vec![Presence { start: date("2022-10-01"), days: 200 }],
+			arrival: Some(date("2023-01-10")),
+			..Default::default()
+		};
+		let whole_year = |year: u32, estatuto_fiscal| {
+			vec![ResidencePeriod {
+				first: Date::first_of_year(year),
+				last: Date::last_of_year(year),
+				estatuto_fiscal,
+			}]
+		};
+		assert_eq!(residence(2022, &whereabouts).unwrap(), whole_year(2022, EstatutoFiscal::NãoResidente));
+		assert_eq!(residence(2023, &whereabouts).unwrap()[1].first, date("2023-01-10"));
+		// Left the year before
+		let whereabouts = Whereabouts {
+			presence: vec![Presence { start: date("2022-03-01"), days: 250 }],
+			departure: Some(date("2022-12-20")),
+			..Default::default()
+		};
+		assert_eq!(residence(2023, &whereabouts).unwrap(), whole_year(2023, EstatutoFiscal::NãoResidente));
+	}
+
+	#[test]
+	fn invalid_whereabouts() {
+		let whereabouts = Whereabouts {
+			arrival: Some(date("2022-09-15")),
+			departure: Some(date("2022-03-31")),
+			..Default::default()
+		};
+		assert!(matches!(residence(2022, &whereabouts), Err(Error::InvalidContext(_))));
+		let whereabouts = Whereabouts {
+			presence: vec![Presence { start: date("2022-01-01"), days: 400 }],
+			..Default::default()
+		};
+		assert!(matches!(residence(2022, &whereabouts), Err(Error::InvalidContext(_))));
+		assert_eq!(Presence { start: date("2020-02-29"), days: 0 }.end(), date("2021-02-28"));
+	}
+}
@@ -1,29 +1,67 @@
 use derive_builder::Builder;
 
 use crate::{
-	ss,
-	units::{Workdaily, YearlyPlan},
-	FamilyElement, Money, MoneyRate, Monthly, TaxRate, Yearly,
+	calendar::Calendar,
+	irs::{
+		jovem::YoungWorker,
+		EstatutoFiscal,
+		settlement::{self, Expenses, Income, Settlement},
+	},
+	ss::{
+		self,
+		entidade_contratante::{self, ClientServices},
+		incentives::Incentive,
+	},
+	tax_year::TaxYear,
+	timeline::{PaymentSchedule, Timeline},
+	units::{Date, PaymentCounts, Rounding, Workdaily, YearlyPlan},
+	Error, FamilyElement, Money, MoneyRate, Monthly, Result, TaxRate, Yearly,
 };
 
-#[derive(Builder, Debug, Default, Clone, PartialEq)]
+#[derive(Builder, Debug, Clone, PartialEq)]
+#[builder(build_fn(validate = "Self::validate", error = "Error"))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", builder(derive(serde::Deserialize)), serde(try_from = "ContextBuilder"))]
 pub struct Context {
 	#[builder(default = "2022")]
 	pub year: u32,
+	/// First day of the contract, if it may start within `year`.
+	#[builder(default, setter(strip_option))]
+	pub start: Option<Date>,
+	/// Last day of the contract, if it may end within `year`.
+	#[builder(default, setter(strip_option))]
+	pub end: Option<Date>,
 	#[builder(default = "FamilyElement { casado: false, titular_unico: false, dependentes: 0 }")]
 	pub family: FamilyElement,
+	/// The worker, if eligible for IRS Jovem.
+	#[builder(default, setter(strip_option))]
+	pub young_worker: Option<YoungWorker>,
+	#[builder(default)]
+	pub estatuto_fiscal: EstatutoFiscal,
+	#[builder(default)]
+	pub ss_regime: ss::Regime,
+	/// Reduction of contributions the company benefits from, counted from
+	/// the contract's `start`.
+	#[builder(default, setter(strip_option))]
+	pub incentive: Option<Incentive>,
 	#[builder(default = "22")]
 	pub vacation_days: u32,
+	#[builder(default = "Calendar::default()")]
+	pub calendar: Calendar,
+	#[builder(default = "PaymentSchedule::default()")]
+	pub payment_schedule: PaymentSchedule,
 	#[builder(default = "MoneyRate::<Yearly>::new(4.0, Yearly)")]
 	pub meal_card_cost: MoneyRate<Yearly>,
 	#[builder(default = "0.0078")]
 	pub meal_card_tax: TaxRate,
 	#[builder(default = "0.01")]
 	pub salary_guarantee_fund_tax: TaxRate,
-	#[builder(default = "ss::tax::EMPRESA")]
-	pub company_tsu: TaxRate,
-	#[builder(default = "ss::tax::TRABALHADOR")]
-	pub worker_tsu: TaxRate,
+	/// Company's SS rate, if not the year's.
+	#[builder(default, setter(strip_option))]
+	pub company_tsu: Option<TaxRate>,
+	/// Worker's SS rate, if not the year's.
+	#[builder(default, setter(strip_option))]
+	pub worker_tsu: Option<TaxRate>,
 	#[builder(default = "false")]
 	pub impute_travel_expenses: bool, // to client
 	#[builder(default = "0.05")]
@@ -38,6 +76,59 @@ pub struct Context {
 	pub insurance_inem_tax: TaxRate,
 }
 
+impl Default for Context {
+	fn default() -> Self { ContextBuilder::default().build().expect("the default context is valid") }
+}
+
+// Deserialized contexts go through the builder, so they're checked like built ones
+#[cfg(feature = "serde")]
+impl TryFrom<ContextBuilder> for Context {
+	type Error = Error;
+
+	fn try_from(builder: ContextBuilder) -> Result<Self> { builder.build() }
+}
+
+impl ContextBuilder {
+	fn validate(&self) -> Result<()> {
+		let rates = [
+			("meal_card_tax", self.meal_card_tax),
+			("salary_guarantee_fund_tax", self.salary_guarantee_fund_tax),
+			("company_tsu", self.company_tsu.flatten()),
+			("worker_tsu", self.worker_tsu.flatten()),
+			("unimputed_travel_expenses_tax", self.unimputed_travel_expenses_tax),
+			("work_insurance_tax", self.work_insurance_tax),
+			("work_accident_fund_tax", self.work_accident_fund_tax),
+			("insurance_stamp_tax", self.insurance_stamp_tax),
+			("insurance_inem_tax", self.insurance_inem_tax),
+		];
+		for (name, rate) in rates {
+			match rate {
+				Some(rate) if !(0.0..=1.0).contains(&rate) => return Err(Error::InvalidRate(name, rate)),
+				_ => {}
+			}
+		}
+		if let Some(schedule) = &self.payment_schedule {
+			schedule.validate()?;
+		}
+		if self.vacation_days == Some(0) {
+			return Err(Error::InvalidContext("no vacation days".to_string()));
+		}
+		if let Some(Some(incentive)) = self.incentive {
+			if incentive.expires() && !matches!(self.start, Some(Some(_))) {
+				let message = format!("{:?} without the contract's start", incentive);
+				return Err(Error::InvalidContext(message));
+			}
+		}
+		if let (Some(Some(start)), Some(Some(end))) = (self.start, self.end) {
+			if start > end {
+				let message = format!("contract ends on {} before starting on {}", end, start);
+				return Err(Error::InvalidContext(message));
+			}
+		}
+		Ok(())
+	}
+}
+
 #[derive(Builder, Debug, Default, Clone, PartialEq)]
 pub struct StateTaxes {
 	#[builder(default = "0.01")]
@@ -56,11 +147,209 @@ pub struct StateTaxes {
 	pub insurance_inem_tax: TaxRate,
 }
 
+impl Context {
+	/// First and last days employed in `year`, if employed at all.
+	pub fn employment_in_year(&self) -> Option<(Date, Date)> {
+		let (first_of_year, last_of_year) = (Date::first_of_year(self.year), Date::last_of_year(self.year));
+		let first = self.start.map_or(first_of_year, |start| start.max(first_of_year));
+		let last = self.end.map_or(last_of_year, |end| end.min(last_of_year));
+		(first <= last).then_some((first, last))
+	}
+
+	/// Share of the year employed.
+	pub fn share_of_year(&self) -> f64 {
+		let days = self.employment_in_year().map_or(0, |(first, last)| first.days_until(&last) + 1);
+		days as f64 / Date::days_in_year(self.year) as f64
+	}
+
+	/// Share of `month` employed, which pro-rates its regular payments.
+	pub fn share_of_month(&self, month: u32) -> Result<f64> {
+		let first_of_month = Date::new(self.year, month, 1)?;
+		let days_in_month = Date::days_in_month(self.year, month);
+		let Some((first, last)) = self.employment_in_year() else { return Ok(0.0) };
+		let first = first.max(first_of_month);
+		let last = last.min(Date::new(self.year, month, days_in_month)?);
+		Ok((first.days_until(&last) + 1).max(0) as f64 / days_in_month as f64)
+	}
+
+	/// Months in which something is paid.
+	pub fn months_employed(&self) -> Option<std::ops::RangeInclusive<u32>> {
+		self.employment_in_year().map(|(first, last)| first.month()..=last.month())
+	}
+
+	fn hired_this_year(&self) -> bool { self.start.is_some_and(|start| start.year() == self.year) }
+
+	fn leaves_this_year(&self) -> bool { self.end.is_some_and(|end| end.year() == self.year) }
+
+	/// Vacation days taken in the year. In the year of admission, 2 days are
+	/// earned per complete month of contract, up to 20 (Código do Trabalho,
+	/// art. 239.º). When the contract ends, the days earned on 1 January are
+	/// taken, and vacation for the time worked in the year is paid instead
+	/// (art. 245.º).
+	pub fn vacation_days_taken(&self) -> u32 {
+		let Some((first, last)) = self.employment_in_year() else { return 0 };
+		match (self.hired_this_year(), self.leaves_this_year()) {
+			(true, true) => 0,
+			(true, false) => {
+				let next = last.add_days(1);
+				let months = (next.year() * 12 + next.month()) - (first.year() * 12 + first.month());
+				(2 * (months - (next.day() < first.day()) as u32)).min(20)
+			}
+			(false, true) => self.vacation_days.min(self.calendar.workdays(first, last)),
+			(false, false) => self.vacation_days,
+		}
+	}
+
+	/// Vacation paid but not taken when the contract ends, in months of pay.
+	pub fn untaken_vacation_share(&self) -> f64 {
+		if !self.leaves_this_year() || self.employment_in_year().is_none() {
+			return 0.0;
+		}
+		let untaken_on_january = match self.hired_this_year() {
+			true => 0.0,
+			false => {
+				let untaken = self.vacation_days - self.vacation_days_taken();
+				untaken as f64 / self.vacation_days.max(1) as f64
+			}
+		};
+		untaken_on_january + self.share_of_year()
+	}
+
+	/// Share of the vacation subsidy, which follows the vacation days earned
+	/// (art. 264.º).
+	pub fn vacation_subsidy_share(&self) -> f64 {
+		if self.employment_in_year().is_none() {
+			return 0.0;
+		}
+		match (self.hired_this_year(), self.leaves_this_year()) {
+			(true, true) => self.share_of_year(),
+			(true, false) => self.vacation_days_taken() as f64 / self.vacation_days.max(1) as f64,
+			(false, true) => 1.0 + self.share_of_year(),
+			(false, false) => 1.0,
+		}
+	}
+
+	/// Share of the Christmas subsidy, proportional to the time worked in the
+	/// years of admission and termination (art. 263.º).
+	pub fn christmas_subsidy_share(&self) -> f64 { self.share_of_year() }
+
+	/// Part of a yearly `income` exempt under IRS Jovem.
+	pub fn irs_jovem_exemption(&self, income: Money) -> Result<Money> {
+		let tax_year = self.tax_year()?;
+		Ok(self.young_worker.map_or(Money::zero(), |worker| worker.exemption(income, tax_year)))
+	}
+
+	pub fn payments_in_year(&self) -> PaymentCounts {
+		let vacation_month = self.payment_schedule.vacation_month;
+		// Months of the year are always valid
+		let share = |month| self.share_of_month(month).unwrap_or_default();
+		let vacation: f64 = (1..=12).filter(|m| *m == vacation_month).map(share).sum();
+		PaymentCounts {
+			regular: (1..=12).filter(|m| *m != vacation_month).map(share).sum(),
+			vacation: vacation + self.untaken_vacation_share(),
+			bonus: self.vacation_subsidy_share() + self.christmas_subsidy_share(),
+		}
+	}
+
+	/// Workdays in the employment period, according to the calendar, once
+	/// vacation is taken.
+	pub fn workdays_in_year(&self) -> u32 {
+		let Some((first, last)) = self.employment_in_year() else { return 0 };
+		self.calendar.workdays(first, last).saturating_sub(self.vacation_days_taken())
+	}
+
+	/// Tax parameters of `year`.
+	pub fn tax_year(&self) -> Result<&'static TaxYear> { TaxYear::get(self.year) }
+
+	/// Company's SS rate, by the regime if it sets one, or else the
+	/// context's or the year's.
+	pub fn company_ss_rate(&self) -> Result<TaxRate> {
+		match (self.ss_regime.rates(), self.company_tsu) {
+			(Some((company, _)), _) | (None, Some(company)) => Ok(company),
+			(None, None) => Ok(self.tax_year()?.ss.empresa),
+		}
+	}
+
+	/// Company's SS rate in `month` of the year, reduced while an incentive lasts.
+	pub fn company_ss_rate_in(&self, month: u32) -> Result<TaxRate> {
+		let months = self.start.map_or(0, |start| {
+			((self.year * 12 + month) as i64 - (start.year() * 12 + start.month()) as i64).max(0)
+		});
+		match self.incentive.and_then(|incentive| incentive.company_rate(months as u32)) {
+			Some(rate) => Ok(rate),
+			None => self.company_ss_rate(),
+		}
+	}
+
+	/// Worker's SS rate, by the incentive or the regime if they set one, or
+	/// else the context's or the year's.
+	pub fn worker_ss_rate(&self) -> Result<TaxRate> {
+		let rate = (self.incentive.and_then(|incentive| incentive.worker_rate()))
+			.or(self.ss_regime.rates().map(|(_, worker)| worker))
+			.or(self.worker_tsu);
+		match rate {
+			Some(rate) => Ok(rate),
+			None => Ok(self.tax_year()?.ss.trabalhador),
+		}
+	}
+
+	/// Whether contributions depend on the month: incentives may change the
+	/// rate during the year, and the base of members of statutory bodies
+	/// depends on everything paid in the month.
+	pub fn contributions_by_month(&self) -> bool {
+		self.incentive.is_some() || self.ss_regime != ss::Regime::TrabalhadorPorContaDeOutrem
+	}
+
+	/// SS base of `month`, in which `taxable` is paid. Members of statutory
+	/// bodies contribute on at least an IAS in each month in office.
+	pub fn ss_base(&self, month: u32, taxable: Money) -> Result<Money> {
+		let in_office = self.months_employed().is_some_and(|months| months.contains(&month));
+		match self.ss_regime {
+			ss::Regime::MembroOrgaoEstatutario { .. } if in_office => {
+				Ok(taxable.max(self.tax_year()?.ias.quantity()))
+			}
+			_ => Ok(taxable),
+		}
+	}
+
+	/// Company's SS contributions on a monthly taxable parcel over the year,
+	/// counted month by month when they depend on it.
+	pub fn company_contributions(&self, ss_taxable: MoneyRate<Monthly>) -> Result<MoneyRate<Yearly>> {
+		let payments = self.payments_in_year();
+		match self.contributions_by_month() {
+			false => Ok(payments.yearly(ss_taxable * self.company_ss_rate()?)),
+			true => {
+				let mut plan = YearlyPlan::default().with_payments(payments);
+				plan += ss_taxable;
+				Timeline::contributions(&plan, self, |month| self.company_ss_rate_in(month))
+			}
+		}
+	}
+
+	/// Salary guarantee fund rate, which members of statutory bodies don't pay.
+	pub fn salary_guarantee_fund_rate(&self) -> TaxRate {
+		match self.ss_regime {
+			ss::Regime::TrabalhadorPorContaDeOutrem => self.salary_guarantee_fund_tax,
+			ss::Regime::MembroOrgaoEstatutario { .. } => 0.0,
+		}
+	}
+
+	/// Rate withheld on a monthly payment of `month`. Ex-residents are
+	/// withheld on the half of their income that's taxed.
+	pub(crate) fn irs_withholding(&self, month: u32, monthly: Money) -> Result<TaxRate> {
+		if let Some(rate) = self.estatuto_fiscal.flat_rate() {
+			return Ok(rate);
+		}
+		let share = self.estatuto_fiscal.taxed_share();
+		Ok(self.tax_year()?.withholding_tables(month)?.tax(monthly * share, &self.family)? * share)
+	}
+}
+
 pub trait Heading: core::fmt::Debug + HeadingBoxClone {
-	fn gross_payment(&self) -> MoneyRate<Monthly>;
-	fn ss_taxable_parcel(&self) -> MoneyRate<Monthly>;
-	fn irs_taxable_parcel(&self) -> MoneyRate<Monthly>;
-	fn company_cost(&self, ctx: &Context) -> MoneyRate<Yearly>;
+	fn gross_payment(&self, ctx: &Context) -> MoneyRate<Monthly>;
+	fn ss_taxable_parcel(&self, ctx: &Context) -> Result<MoneyRate<Monthly>>;
+	fn irs_taxable_parcel(&self, ctx: &Context) -> Result<MoneyRate<Monthly>>;
+	fn company_cost(&self, ctx: &Context) -> Result<MoneyRate<Yearly>>;
 }
 
 // Splitting SalaryHeadingBoxClone into its own trait allows us to provide a
@@ -87,6 +376,7 @@ impl Clone for Box<dyn Heading> {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BaseSalary {
 	monthly: MoneyRate<Monthly>,
 }
@@ -98,85 +388,98 @@ impl BaseSalary {
 	pub fn monthly(&self) -> MoneyRate<Monthly> { self.monthly }
 }
 impl Heading for BaseSalary {
-	fn gross_payment(&self) -> MoneyRate<Monthly> { self.monthly }
+	fn gross_payment(&self, _ctx: &Context) -> MoneyRate<Monthly> { self.monthly }
 
-	fn ss_taxable_parcel(&self) -> MoneyRate<Monthly> { self.gross_payment() }
+	fn ss_taxable_parcel(&self, ctx: &Context) -> Result<MoneyRate<Monthly>> { Ok(self.gross_payment(ctx)) }
 
-	fn irs_taxable_parcel(&self) -> MoneyRate<Monthly> { self.gross_payment() }
+	fn irs_taxable_parcel(&self, ctx: &Context) -> Result<MoneyRate<Monthly>> { Ok(self.gross_payment(ctx)) }
 
-	fn company_cost(&self, ctx: &Context) -> MoneyRate<Yearly> {
-		let payment: MoneyRate<Yearly> = self.gross_payment().into();
-		payment * (1.0 + ctx.company_tsu + ctx.salary_guarantee_fund_tax)
+	fn company_cost(&self, ctx: &Context) -> Result<MoneyRate<Yearly>> {
+		// Contributions are rounded to the cent on each payment, like in the SS declaration
+		let payment = self.gross_payment(ctx);
+		let contribution = |rate: TaxRate| (payment.quantity() * rate).round(Rounding::HalfUp);
+		let cost = payment.quantity() + contribution(ctx.salary_guarantee_fund_rate());
+		let payments = ctx.payments_in_year();
+		let contributions = match ctx.contributions_by_month() {
+			false => payments.yearly(MoneyRate::new(contribution(ctx.company_ss_rate()?), payment.period())),
+			true => ctx.company_contributions(self.ss_taxable_parcel(ctx)?)?,
+		};
+		Ok(payments.yearly(MoneyRate::new(cost, payment.period())) + contributions)
 	}
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum MealAllowance {
 	None,
 	Cash(MoneyRate<Workdaily>),
 	Card(MoneyRate<Workdaily>),
 }
 impl MealAllowance {
-	pub fn cash() -> Self { Self::Cash(crate::non_taxation_limits::SUBSIDIO_REFEICAO) }
+	/// The most that can be paid in cash without being taxed in `tax_year`.
+	pub fn cash(tax_year: &TaxYear) -> Self { Self::Cash(tax_year.non_taxation_limits.subsidio_refeicao) }
+
+	/// The most that can be paid in meal cards without being taxed in `tax_year`.
+	pub fn card(tax_year: &TaxYear) -> Self { Self::Card(tax_year.non_taxation_limits.vale_refeicao) }
 
-	pub fn card() -> Self { Self::Card(crate::non_taxation_limits::VALE_REFEICAO) }
+	pub fn daily(&self) -> MoneyRate<Workdaily> {
+		match self {
+			Self::Card(v) | Self::Cash(v) => *v,
+			Self::None => MoneyRate::new(0.0, Workdaily),
+		}
+	}
 }
 impl Heading for MealAllowance {
-	fn gross_payment(&self) -> MoneyRate<Monthly> {
-		MoneyRate::new(
-			match self {
-				Self::Card(v) => {
-					//crate::non_taxation_limits::VALE_REFEICAO
-					v.into_yearly(None)//Some(Workdaily::actual_workdays_in_year(2022, 22)))
-						.quantity() / 11.0
-				}
-				Self::Cash(v) => {
-					//crate::non_taxation_limits::SUBSIDIO_REFEICAO
-					v.into_yearly(None)//Some(Workdaily::actual_workdays_in_year(2022, 22)))
-						.quantity() / 11.0
-				}
-				Self::None => 0.0.into(),
-			},
-			Monthly::M11,
-		)
-	}
-
-	fn ss_taxable_parcel(&self) -> MoneyRate<Monthly> { self.irs_taxable_parcel() }
-
-	fn irs_taxable_parcel(&self) -> MoneyRate<Monthly> { MoneyRate::new(0.0, Monthly::M11) }
-
-	fn company_cost(&self, ctx: &Context) -> MoneyRate<Yearly> {
-		let yearly_paid = MoneyRate::<Yearly>::from(self.gross_payment());
+	/// The workdays actually worked in the year, spread over the months that
+	/// aren't the vacation month.
+	fn gross_payment(&self, ctx: &Context) -> MoneyRate<Monthly> {
+		let yearly = self.daily().into_yearly(Some(ctx.workdays_in_year()));
+		let months = ctx.payments_in_year().regular;
+		MoneyRate::new(if months > 0.0 { yearly.quantity() / months } else { Money::zero() }, Monthly::M11)
+	}
+
+	fn ss_taxable_parcel(&self, ctx: &Context) -> Result<MoneyRate<Monthly>> { self.irs_taxable_parcel(ctx) }
+
+	fn irs_taxable_parcel(&self, _ctx: &Context) -> Result<MoneyRate<Monthly>> {
+		Ok(MoneyRate::new(0.0, Monthly::M11))
+	}
+
+	fn company_cost(&self, ctx: &Context) -> Result<MoneyRate<Yearly>> {
+		let yearly_paid = ctx.payments_in_year().yearly(self.gross_payment(ctx));
 		let card_cost = match self {
 			Self::Card(_) => ctx.meal_card_cost + yearly_paid * ctx.meal_card_tax,
 			_ => MoneyRate::zero(),
 		};
-		yearly_paid + card_cost
+		Ok(yearly_paid + card_cost)
 	}
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TravelExpenses {
 	pub monthly: MoneyRate<Monthly>,
 }
 
 impl Heading for TravelExpenses {
-	fn gross_payment(&self) -> MoneyRate<Monthly> { self.monthly }
+	fn gross_payment(&self, _ctx: &Context) -> MoneyRate<Monthly> { self.monthly }
 
-	fn ss_taxable_parcel(&self) -> MoneyRate<Monthly> { self.irs_taxable_parcel() }
+	fn ss_taxable_parcel(&self, ctx: &Context) -> Result<MoneyRate<Monthly>> { self.irs_taxable_parcel(ctx) }
 
-	fn irs_taxable_parcel(&self) -> MoneyRate<Monthly> { MoneyRate::new(0.0, Monthly::M11) }
+	fn irs_taxable_parcel(&self, _ctx: &Context) -> Result<MoneyRate<Monthly>> {
+		Ok(MoneyRate::new(0.0, Monthly::M11))
+	}
 
-	fn company_cost(&self, ctx: &Context) -> MoneyRate<Yearly> {
-		let mut payment: MoneyRate<Yearly> = self.gross_payment().into();
+	fn company_cost(&self, ctx: &Context) -> Result<MoneyRate<Yearly>> {
+		let mut payment = ctx.payments_in_year().yearly(self.gross_payment(ctx));
 		if !ctx.impute_travel_expenses {
 			payment *= 1.0 + ctx.unimputed_travel_expenses_tax;
 		}
-		payment
+		Ok(payment)
 	}
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RetirementFunds {
 	pub monthly: MoneyRate<Monthly>,
 }
@@ -187,16 +490,21 @@ impl RetirementFunds {
 }
 
 impl Heading for RetirementFunds {
-	fn gross_payment(&self) -> MoneyRate<Monthly> { self.monthly }
+	fn gross_payment(&self, _ctx: &Context) -> MoneyRate<Monthly> { self.monthly }
 
-	fn ss_taxable_parcel(&self) -> MoneyRate<Monthly> { MoneyRate::new(0.0, Monthly::M12) }
+	fn ss_taxable_parcel(&self, _ctx: &Context) -> Result<MoneyRate<Monthly>> {
+		Ok(MoneyRate::new(0.0, Monthly::M12))
+	}
 
-	fn irs_taxable_parcel(&self) -> MoneyRate<Monthly> { self.gross_payment() }
+	fn irs_taxable_parcel(&self, ctx: &Context) -> Result<MoneyRate<Monthly>> { Ok(self.gross_payment(ctx)) }
 
-	fn company_cost(&self, _ctx: &Context) -> MoneyRate<Yearly> { (self.gross_payment() * 1.02).into() }
+	fn company_cost(&self, ctx: &Context) -> Result<MoneyRate<Yearly>> {
+		Ok(ctx.payments_in_year().yearly(self.gross_payment(ctx) * 1.02))
+	}
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Salary {
 	pub base_salary: BaseSalary,
 	pub meal_allowance: MealAllowance,
@@ -242,44 +550,112 @@ impl Salary {
 	}
 
 	pub fn work_accidents_insurance(&self, ctx: &Context) -> MoneyRate<Yearly> {
-		let capital = self.base_salary.gross_payment() + self.meal_allowance.gross_payment();
+		let capital = crate::insurances::work_accidents::coverage_capital(self);
 		// Nota: criação/alteração de apólice conta como premium para efeitos de aplicação de taxas
+		// Premiums are charged on the salaries actually declared
+		let capital = capital * ctx.share_of_year();
 		let premium = capital * ctx.work_insurance_tax;
 		(premium + capital * ctx.work_accident_fund_tax) * (1.0 + ctx.insurance_stamp_tax)
 			+ premium * ctx.insurance_inem_tax
 	}
 
-	pub fn company_cost(&self, ctx: &Context) -> MoneyRate<Yearly> {
-		let c1 = self.base_salary.company_cost(ctx);
-		let c2 = self.meal_allowance.company_cost(ctx);
-		let c3 = self.travel_expenses.company_cost(ctx);
-		let c4 = self.retirement_funds.company_cost(ctx);
+	pub fn company_cost(&self, ctx: &Context) -> Result<MoneyRate<Yearly>> {
+		let c1 = self.base_salary.company_cost(ctx)?;
+		let c2 = self.meal_allowance.company_cost(ctx)?;
+		let c3 = self.travel_expenses.company_cost(ctx)?;
+		let c4 = self.retirement_funds.company_cost(ctx)?;
 		let c5 = self.work_accidents_insurance(ctx);
-		c1 + c2 + c3 + c4 + c5
+		Ok(c1 + c2 + c3 + c4 + c5)
 	}
 
-	pub fn yearly_plan_withhold_net(&self, ctx: &Context) -> YearlyPlan<Money> {
-		let headings = self.headings();
-		let plan: YearlyPlan<_> = headings.iter().map(|h| h.gross_payment()).into();
-		let ss_taxable_parcel: YearlyPlan<_> = headings.iter().map(|h| h.ss_taxable_parcel()).into();
-		let irs_taxable_parcel: YearlyPlan<_> = headings.iter().map(|h| h.irs_taxable_parcel()).into();
-		let irs_withholding = match ctx.year {
-			2022 => |v| v * crate::irs::withholding::year_2022::tax(v, &ctx.family),
-			_ => unimplemented!(),
+	pub fn yearly_plan_withhold_net(&self, ctx: &Context) -> Result<YearlyPlan<Money>> {
+		let [plan, ss_taxable_parcel, irs_taxable_parcel] = self.yearly_plans(ctx)?;
+		// Withheld with the tables in force when the year's employment starts
+		let month = ctx.months_employed().map_or(1, |months| *months.start());
+		let irs = Self::withholding_base(irs_taxable_parcel, ctx)?
+			.try_map(|v| ctx.irs_withholding(month, v).map(|rate| (v * rate).round(Rounding::Truncate)))?;
+		Ok(plan - Self::worker_contributions(ss_taxable_parcel, ctx)? - irs)
+	}
+
+	/// Month-by-month payments, with SS contributions and IRS withheld on each.
+	pub fn timeline(&self, ctx: &Context) -> Result<Timeline> {
+		let [plan, ss_taxable_parcel, irs_taxable_parcel] = self.yearly_plans(ctx)?;
+		let irs_taxable_parcel = Self::withholding_base(irs_taxable_parcel, ctx)?;
+		Timeline::new(&plan, &ss_taxable_parcel, &irs_taxable_parcel, ctx)
+	}
+
+	/// Net income once the year's IRS is settled, with no deductions from the
+	/// tax besides the family's dependents.
+	pub fn yearly_plan_real_net(&self, ctx: &Context) -> Result<MoneyRate<Yearly>> {
+		let [plan, ss_taxable_parcel, irs_taxable_parcel] = self.yearly_plans(ctx)?;
+		let ss = Self::worker_contributions(ss_taxable_parcel, ctx)?.yearly_total().quantity();
+		let gross = irs_taxable_parcel.yearly_total().quantity();
+		let exempt = ctx.irs_jovem_exemption(gross)?;
+		let income = Income { gross, exempt, ss_contributions: ss, ..Default::default() };
+		let settlement = settlement::settle(&income, &Expenses::default(), ctx)?;
+		Ok(plan.yearly_total() - MoneyRate::new(ss + settlement.tax, Yearly))
+	}
+
+	/// Annual IRS settlement of the salary, against the IRS withheld over the
+	/// months of the year.
+	pub fn settlement(&self, expenses: &Expenses, ctx: &Context) -> Result<Settlement> {
+		let [plan, ss_taxable_parcel, irs_taxable_parcel] = self.yearly_plans(ctx)?;
+		let gross = irs_taxable_parcel.yearly_total().quantity();
+		let irs_taxable_parcel = Self::withholding_base(irs_taxable_parcel, ctx)?;
+		let timeline = Timeline::new(&plan, &ss_taxable_parcel, &irs_taxable_parcel, ctx)?;
+		let income = Income {
+			gross,
+			exempt: ctx.irs_jovem_exemption(gross)?,
+			ss_contributions: timeline.ss().quantity(),
+			withheld: timeline.irs().quantity(),
+			cat_b: Money::zero(),
+			cat_b_exempt: Money::zero(),
 		};
-		let irs = irs_taxable_parcel.map(irs_withholding);
-		plan - ss_taxable_parcel * ctx.worker_tsu - irs
+		settlement::settle(&income, expenses, ctx)
 	}
 
-	pub fn yearly_plan_real_net(&self, ctx: &Context) -> MoneyRate<Yearly> {
+	/// Gross payments, and their SS and IRS taxable parcels.
+	fn yearly_plans(&self, ctx: &Context) -> Result<[YearlyPlan<Money>; 3]> {
 		let headings = self.headings();
-		let plan: YearlyPlan<_> = headings.iter().map(|h| h.gross_payment()).into();
-		let ss_taxable_parcel: YearlyPlan<_> = headings.iter().map(|h| h.ss_taxable_parcel()).into();
-		let irs_taxable_parcel: YearlyPlan<_> = headings.iter().map(|h| h.irs_taxable_parcel()).into();
-		// TODO: Add context to brackets calculation!
-		let irs_tax =
-			crate::irs::brackets::taxes(irs_taxable_parcel.yearly_total(), &crate::irs::brackets::year_2022);
-		(plan - ss_taxable_parcel * ctx.worker_tsu).yearly_total() - irs_tax
+		let payments = ctx.payments_in_year();
+		let plan = YearlyPlan::from(headings.iter().map(|h| h.gross_payment(ctx)));
+		let ss_taxable_parcel: Result<Vec<_>> = headings.iter().map(|h| h.ss_taxable_parcel(ctx)).collect();
+		let irs_taxable_parcel: Result<Vec<_>> = headings.iter().map(|h| h.irs_taxable_parcel(ctx)).collect();
+		Ok([
+			plan.with_payments(payments),
+			YearlyPlan::from(ss_taxable_parcel?.into_iter()).with_payments(payments),
+			YearlyPlan::from(irs_taxable_parcel?.into_iter()).with_payments(payments),
+		])
+	}
+
+	/// IRS taxable parcels withheld on, without the IRS Jovem exemption in
+	/// the years it's taken into account when withholding.
+	fn withholding_base(irs_taxable_parcel: YearlyPlan<Money>, ctx: &Context) -> Result<YearlyPlan<Money>> {
+		if !ctx.tax_year()?.irs_jovem.is_some_and(|rules| rules.withholding) {
+			return Ok(irs_taxable_parcel);
+		}
+		let gross = irs_taxable_parcel.yearly_total().quantity();
+		let exempt = ctx.irs_jovem_exemption(gross)?;
+		if exempt == Money::zero() {
+			return Ok(irs_taxable_parcel);
+		}
+		let taxed = 1.0 - exempt.value() / gross.value();
+		Ok(irs_taxable_parcel.map(|v| v * taxed))
+	}
+
+	/// Worker's contributions on each payment. When the base depends on the
+	/// month, what it adds over the year is spread over the regular payments.
+	fn worker_contributions(
+		ss_taxable_parcel: YearlyPlan<Money>, ctx: &Context,
+	) -> Result<YearlyPlan<Money>> {
+		let rate = ctx.worker_ss_rate()?;
+		let mut contributions = ss_taxable_parcel.clone().map(|v| (v * rate).round(Rounding::HalfUp));
+		if ctx.ss_regime != ss::Regime::TrabalhadorPorContaDeOutrem && contributions.payments.regular > 0.0 {
+			let by_month = Timeline::contributions(&ss_taxable_parcel, ctx, |_| Ok(rate))?;
+			let added = by_month.quantity() - contributions.yearly_total().quantity();
+			contributions.regular += added / contributions.payments.regular;
+		}
+		Ok(contributions)
 	}
 
 	fn headings(&self) -> [&dyn Heading; 4] {
@@ -292,27 +668,56 @@ impl Salary {
 	}
 }
 
+/// An independent worker paid through recibos verdes, to compare with what a
+/// [`Salary`] costs the company.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Freelancer {
+	/// The company hiring the worker, among the clients of `services`.
+	pub client: String,
+	/// Services the worker invoiced over the year, to every client.
+	pub services: Vec<ClientServices>,
+}
+
+impl Freelancer {
+	/// Yearly cost to the client: the services it bought, plus its
+	/// contributions as contracting entity.
+	pub fn company_cost(&self, ctx: &Context) -> Result<MoneyRate<Yearly>> {
+		let bought: Money = (self.services.iter())
+			.filter(|services| services.client == self.client)
+			.map(|services| services.services)
+			.sum();
+		let contribution = entidade_contratante::contributions(&self.services, ctx.tax_year()?)?
+			.filter(|entity| entity.client == self.client)
+			.map_or(Money::zero(), |entity| entity.contribution);
+		Ok(MoneyRate::new(bought + contribution, Yearly))
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
 
-	fn assert_eq(yearly: MoneyRate<Yearly>, expected: f64) {
-		assert!((yearly.quantity() - expected.into()).abs() < 0.001.into())
+	fn assert_eq(yearly: Result<MoneyRate<Yearly>>, expected: f64) {
+		assert!((yearly.unwrap().quantity() - expected.into()).abs() < 0.001.into())
 	}
 
 	#[test]
 	fn base_salary_company_cost() {
-		let ctx = ContextBuilder::default().build().unwrap();
+		let ctx = Context::default();
 		assert_eq(BaseSalary::new(1000.0).company_cost(&ctx), 1000.0 * (1.2375 + 0.01) * 14.0);
 	}
 
 	#[test]
 	fn meal_allowance_company_cost() {
-		use crate::non_taxation_limits::{SUBSIDIO_REFEICAO, VALE_REFEICAO};
-		let ctx = ContextBuilder::default().build().unwrap();
+		let ctx = Context::default();
+		let tax_year = ctx.tax_year().unwrap();
 		assert_eq(MealAllowance::None.company_cost(&ctx), 0.0);
-		assert_eq(MealAllowance::Cash(SUBSIDIO_REFEICAO).company_cost(&ctx), 4.77 * 22.0 * 11.0);
-		assert_eq(MealAllowance::Card(VALE_REFEICAO).company_cost(&ctx), 7.63 * 22.0 * 11.0 * 1.0078 + 4.0);
+		// 251 workdays in 2022, minus 22 days of vacation
+		assert_eq(MealAllowance::cash(tax_year).company_cost(&ctx), 4.77 * 229.0);
+		assert_eq(MealAllowance::card(tax_year).company_cost(&ctx), 7.63 * 229.0 * 1.0078 + 4.0);
+		let ctx = ContextBuilder::default().year(2023).vacation_days(25).build().unwrap();
+		assert_eq(MealAllowance::cash(tax_year).company_cost(&ctx), 4.77 * (250.0 - 25.0));
 	}
 
 	#[test]
@@ -325,7 +730,178 @@ mod tests {
 
 	#[test]
 	fn retirement_fund_company_cost() {
-		let ctx = ContextBuilder::default().build().unwrap();
+		let ctx = Context::default();
 		assert_eq(RetirementFunds::new(1000.0).company_cost(&ctx), 1000.0 * 12.0 * 1.02);
 	}
+
+	#[test]
+	fn freelancer_company_cost() {
+		let services = |client: &str, services: f64| ClientServices {
+			client: client.to_string(),
+			services: Money::new(services),
+		};
+		let freelancer = |client: &str, invoiced: &[ClientServices]| Freelancer {
+			client: client.to_string(),
+			services: invoiced.to_vec(),
+		};
+		let ctx = Context::default();
+		let invoiced = [services("us", 9000.0), services("them", 1000.0)];
+		// 10% on 90% of the services
+		assert_eq(freelancer("us", &invoiced).company_cost(&ctx), 9900.0);
+		assert_eq(freelancer("them", &invoiced).company_cost(&ctx), 1000.0);
+		// Over the 6 IAS of 2022 (2659.20€), but not of 2023 (2882.58€)
+		let invoiced = [services("us", 2700.0)];
+		assert_eq(freelancer("us", &invoiced).company_cost(&ctx), 2970.0);
+		let ctx = ContextBuilder::default().year(2023).build().unwrap();
+		assert_eq(freelancer("us", &invoiced).company_cost(&ctx), 2700.0);
+	}
+
+	#[test]
+	fn membro_orgao_estatutario() {
+		let gerente = ss::Regime::MembroOrgaoEstatutario { gerencia: true };
+		let ctx = ContextBuilder::default().ss_regime(gerente).build().unwrap();
+		// No salary guarantee fund
+		assert_eq(BaseSalary::new(1000.0).company_cost(&ctx), 1000.0 * 1.2375 * 14.0);
+		let vogal = ss::Regime::MembroOrgaoEstatutario { gerencia: false };
+		let ctx = ContextBuilder::default().ss_regime(vogal).build().unwrap();
+		assert_eq(BaseSalary::new(1000.0).company_cost(&ctx), 1000.0 * 1.203 * 14.0);
+		let salary = Salary::new(1000.0, MealAllowance::None, 0.0);
+		let plan = salary.yearly_plan_withhold_net(&ctx).unwrap();
+		let employee = salary.yearly_plan_withhold_net(&Context::default()).unwrap();
+		assert_eq!(plan.regular - employee.regular, Money::new(1000.0 * (0.11 - 0.093)));
+		// Contributions on at least the IAS of 443.20€ in each month, unless the
+		// month's subsidy already takes it over
+		assert_eq(BaseSalary::new(300.0).company_cost(&ctx), 300.0 * 14.0 + 89.97 * 10.0 + 121.80 * 2.0);
+		let salary = Salary::new(300.0, MealAllowance::None, 0.0);
+		let timeline = salary.timeline(&ctx).unwrap();
+		assert_eq!(timeline.month(1).unwrap().ss, Money::new(41.22));
+		assert_eq!(timeline.month(6).unwrap().ss, Money::new(55.80));
+		// The yearly plan spreads what the IAS adds over the regular payments
+		let plan = salary.yearly_plan_withhold_net(&ctx).unwrap();
+		assert_eq(Ok(plan.yearly_total()), timeline.net().quantity().value());
+	}
+
+	#[test]
+	fn hiring_incentives() {
+		let date = |s: &str| s.parse::<Date>().unwrap();
+		let first_job = |year| {
+			let mut builder = ContextBuilder::default();
+			builder.year(year).start(date("2018-07-01")).incentive(Incentive::FirstJob).build().unwrap()
+		};
+		assert_eq(BaseSalary::new(1000.0).company_cost(&first_job(2022)), 1000.0 * (1.11875 + 0.01) * 14.0);
+		// Five years after hiring, from July, with the vacation subsidy paid in June
+		let reduced = 7000.0 * 0.11875;
+		let general = 7000.0 * 0.2375;
+		assert_eq(BaseSalary::new(1000.0).company_cost(&first_job(2023)), 14_140.0 + reduced + general);
+		let ctx = ContextBuilder::default().incentive(Incentive::OldAgePensioner).build().unwrap();
+		assert_eq(BaseSalary::new(1000.0).company_cost(&ctx), 1000.0 * (1.164 + 0.01) * 14.0);
+		let salary = Salary::new(1000.0, MealAllowance::None, 0.0);
+		let plan = salary.yearly_plan_withhold_net(&ctx).unwrap();
+		let employee = salary.yearly_plan_withhold_net(&Context::default()).unwrap();
+		assert_eq!(plan.regular - employee.regular, Money::new(1000.0 * (0.11 - 0.075)));
+		let unknown_start = ContextBuilder::default().incentive(Incentive::VeryLongTermUnemployed).build();
+		assert!(matches!(unknown_start, Err(Error::InvalidContext(_))));
+	}
+
+	#[test]
+	fn partial_year() {
+		let date = |s: &str| s.parse::<Date>().unwrap();
+		// 9 complete months, from 15 March, earn 18 vacation days
+		let ctx = ContextBuilder::default().start(date("2022-03-15")).build().unwrap();
+		assert_eq!(ctx.vacation_days_taken(), 18);
+		let payments = ctx.payments_in_year();
+		assert!((payments.regular - (8.0 + 17.0 / 31.0)).abs() < 1e-9);
+		assert!((payments.bonus - (18.0 / 22.0 + 292.0 / 365.0)).abs() < 1e-9);
+		let payments = 8.0 + 17.0 / 31.0 + 1.0 + 18.0 / 22.0 + 292.0 / 365.0;
+		assert_eq(BaseSalary::new(1000.0).company_cost(&ctx), 1000.0 * (1.2375 + 0.01) * payments);
+
+		// Leaving on 30 June pays vacation and its subsidy for the first half of the year
+		let ctx = ContextBuilder::default().end(date("2022-06-30")).build().unwrap();
+		let payments = ctx.payments_in_year();
+		assert_eq!(payments.regular, 6.0);
+		assert!((payments.vacation - 181.0 / 365.0).abs() < 1e-9);
+		assert!((payments.bonus - (1.0 + 2.0 * 181.0 / 365.0)).abs() < 1e-9);
+		let salary = Salary::new(1000.0, MealAllowance::cash(ctx.tax_year().unwrap()), 0.0);
+		let gross = 1000.0 * (7.0 + 3.0 * 181.0 / 365.0) + 4.77 * (125.0 - 22.0);
+		assert_eq(salary.yearly_plans(&ctx).map(|[plan, _, _]| plan.yearly_total()), gross);
+
+		let ctx = ContextBuilder::default().end(date("2021-12-31")).build().unwrap();
+		assert_eq(salary.company_cost(&ctx), 0.0);
+	}
+
+	#[test]
+	fn tax_year_changes_calculations() {
+		let salary = Salary::new(2000.0, MealAllowance::None, 0.0);
+		let net = |year| {
+			let ctx = ContextBuilder::default().year(year).build().unwrap();
+			salary.yearly_plan_real_net(&ctx).unwrap()
+		};
+		// Same brackets and specific deduction, but a different calendar
+		assert_eq!(net(2020), net(2021));
+		assert!(net(2021) < net(2022));
+		assert!(net(2023) < net(2024));
+		assert!(net(2024) < net(2025));
+		// 2023's withholding tables changed in July
+		let ctx = ContextBuilder::default().year(2023).build().unwrap();
+		let timeline = salary.timeline(&ctx).unwrap();
+		assert!(timeline.month(7).unwrap().irs < timeline.month(6).unwrap().irs);
+		let start = "2023-09-01".parse().unwrap();
+		let hired = ContextBuilder::default().year(2023).start(start).build().unwrap();
+		let plan = salary.yearly_plan_withhold_net(&hired).unwrap();
+		assert_eq!(plan.regular, timeline.month(9).unwrap().net());
+		// Married taxpayers have their own tables in every year
+		for year in 2019..=2026 {
+			for titular_unico in [false, true] {
+				let family = FamilyElement::new(true, titular_unico, 0).unwrap();
+				let ctx = ContextBuilder::default().year(year).family(family).build().unwrap();
+				assert!(salary.timeline(&ctx).is_ok());
+			}
+		}
+	}
+
+	#[test]
+	fn errors() {
+		let salary = Salary::new(1000.0, MealAllowance::None, 0.0);
+		let ctx = ContextBuilder::default().year(2030).build().unwrap();
+		assert!(matches!(salary.yearly_plan_withhold_net(&ctx), Err(Error::UnsupportedYear(2030))));
+		assert!(matches!(salary.yearly_plan_real_net(&ctx), Err(Error::UnsupportedYear(2030))));
+		// SS rates come from the year, unless the context sets them
+		assert!(matches!(ctx.worker_ss_rate(), Err(Error::UnsupportedYear(2030))));
+		let ctx = ContextBuilder::default().year(2030).worker_tsu(0.1).build().unwrap();
+		assert_eq!(ctx.worker_ss_rate().unwrap(), 0.1);
+
+		assert!(matches!(ContextBuilder::default().worker_tsu(11.0).build(), Err(Error::InvalidRate(_, _))));
+		let date = |s: &str| s.parse::<Date>().unwrap();
+		let reversed = ContextBuilder::default().start(date("2022-06-01")).end(date("2022-05-31")).build();
+		assert!(matches!(reversed, Err(Error::InvalidContext(_))));
+		let no_vacation = ContextBuilder::default().vacation_days(0).build();
+		assert!(matches!(no_vacation, Err(Error::InvalidContext(_))));
+		assert!(matches!(Context::default().share_of_month(13), Err(Error::Units(_))));
+		assert!(matches!(FamilyElement::new(false, true, 0), Err(Error::InvalidFamily(_))));
+	}
+
+	#[cfg(feature = "serde")]
+	#[test]
+	fn serde_round_trip() {
+		let json = serde_json::to_string(&BaseSalary::new(1000.0)).unwrap();
+		assert_eq!(json, r#"{"monthly":{"quantity":1000.0,"period":"M14"}}"#);
+		let tax_year = TaxYear::get(2022).unwrap();
+		let json = serde_json::to_string(&MealAllowance::card(tax_year)).unwrap();
+		assert_eq!(json, r#"{"Card":{"quantity":7.63,"period":"Workdaily"}}"#);
+
+		let salary = Salary::new(1270.0, MealAllowance::card(tax_year), 400.0);
+		let json = serde_json::to_string(&salary).unwrap();
+		let parsed: Salary = serde_json::from_str(&json).unwrap();
+		assert_eq!(serde_json::to_string(&parsed).unwrap(), json);
+
+		let ctx = Context::default();
+		let parsed: Context = serde_json::from_str(&serde_json::to_string(&ctx).unwrap()).unwrap();
+		assert_eq!(parsed, ctx);
+		// Deserialized contexts are checked like built ones
+		let json = serde_json::to_string(&ctx).unwrap();
+		let json = json.replace(r#""vacation_month":8"#, r#""vacation_month":13"#);
+		assert!(serde_json::from_str::<Context>(&json).is_err());
+		let parsed: Context = serde_json::from_str(r#"{"year":2023,"vacation_days":25}"#).unwrap();
+		assert_eq!(parsed, ContextBuilder::default().year(2023).vacation_days(25).build().unwrap());
+	}
 }
@@ -0,0 +1,96 @@
+//! Contributions of the entities that buy most of an independent worker's
+//! services (Código Contributivo, art. 140.º and 168.º).
+
+use super::limites;
+use crate::{
+	tax_year::TaxYear,
+	units::{Money, Rounding, TaxRate},
+	Error, Result,
+};
+
+/// Services an independent worker invoiced to a client over a year.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ClientServices {
+	pub client: String,
+	pub services: Money,
+}
+
+/// What a contracting entity owes on the services it bought.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct EntityContribution {
+	pub client: String,
+	pub services: Money,
+	/// Share of the worker's services bought by the client.
+	pub dependency: f64,
+	pub rate: TaxRate,
+	pub contribution: Money,
+}
+
+/// Rate due by a client buying `dependency` of the worker's services: 10%
+/// above 80%, 7% above 50%.
+pub fn rate(dependency: f64) -> Option<TaxRate> {
+	match dependency {
+		d if d > 0.8 => Some(0.10),
+		d if d > 0.5 => Some(0.07),
+		_ => None,
+	}
+}
+
+/// The contracting entity among a worker's clients, if any. Nothing is due
+/// when the worker's services don't reach 6 IAS in the year.
+pub fn contributions(services: &[ClientServices], tax_year: &TaxYear) -> Result<Option<EntityContribution>> {
+	if let Some(services) = services.iter().find(|services| services.services < Money::zero()) {
+		return Err(Error::InvalidIncome(services.services));
+	}
+	let total: Money = services.iter().map(|services| services.services).sum();
+	if total < limites::serv_min_entidade_contratante(tax_year).quantity() {
+		return Ok(None);
+	}
+	let mut clients: Vec<(&str, Money)> = Vec::new();
+	for services in services {
+		match clients.iter_mut().find(|(client, _)| *client == services.client) {
+			Some((_, bought)) => *bought += services.services,
+			None => clients.push((&services.client, services.services)),
+		}
+	}
+	Ok(clients.into_iter().find_map(|(client, bought)| {
+		let dependency = bought.value() / total.value();
+		rate(dependency).map(|rate| EntityContribution {
+			client: client.to_string(),
+			services: bought,
+			dependency,
+			rate,
+			contribution: (bought * rate).round(Rounding::HalfUp),
+		})
+	}))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn services(client: &str, services: f64) -> ClientServices {
+		ClientServices { client: client.to_string(), services: Money::new(services) }
+	}
+
+	#[test]
+	fn dependency() {
+		let tax_year = TaxYear::get(2022).unwrap();
+		let invoiced = [services("us", 6000.0), services("them", 1000.0), services("us", 3000.0)];
+		let entity = contributions(&invoiced, tax_year).unwrap().unwrap();
+		assert_eq!(entity.client, "us");
+		assert_eq!(entity.rate, 0.10);
+		assert_eq!(entity.contribution, Money::new(900.0));
+		let invoiced = [services("us", 6000.0), services("them", 4000.0)];
+		assert_eq!(contributions(&invoiced, tax_year).unwrap().unwrap().contribution, Money::new(420.0));
+		let invoiced = [services("us", 5000.0), services("them", 5000.0)];
+		assert_eq!(contributions(&invoiced, tax_year).unwrap(), None);
+		// Under 6 IAS
+		let invoiced = [services("us", 2000.0)];
+		assert_eq!(contributions(&invoiced, tax_year).unwrap(), None);
+		let invoiced = [services("us", -1.0)];
+		assert!(matches!(contributions(&invoiced, tax_year), Err(Error::InvalidIncome(_))));
+	}
+}
@@ -0,0 +1,86 @@
+//! Reductions and exemptions of the company's contributions when hiring some
+//! workers (Decreto-Lei 72/2017, and Código Contributivo, art. 96.º, 104.º
+//! and 106.º).
+
+use crate::units::TaxRate;
+
+/// Company's rate for some months of the contract.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Phase {
+	pub rate: TaxRate,
+	/// Months it lasts, or until the contract ends.
+	pub months: Option<u32>,
+}
+
+/// Incentive the company benefits from for a worker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Incentive {
+	/// Young worker looking for a first job, hired without a term.
+	FirstJob,
+	/// Unemployed for 12 months or more, hired without a term.
+	LongTermUnemployed,
+	/// Unemployed for 25 months or more, and 45 years old or more.
+	VeryLongTermUnemployed,
+	/// Disability of 60% or more.
+	Disability,
+	/// Old-age pensioner, usually over 65, still working.
+	OldAgePensioner,
+	/// Disability pensioner still working.
+	DisabilityPensioner,
+}
+
+impl Incentive {
+	/// Company's rates from the month of hiring. Once they run out, the general
+	/// rate applies.
+	pub fn phases(&self) -> &'static [Phase] {
+		match self {
+			Self::FirstJob | Self::LongTermUnemployed => &[Phase { rate: 0.11875, months: Some(60) }],
+			Self::VeryLongTermUnemployed => &[Phase { rate: 0.0, months: Some(36) }],
+			Self::Disability => &[Phase { rate: 0.119, months: None }],
+			Self::OldAgePensioner => &[Phase { rate: 0.164, months: None }],
+			Self::DisabilityPensioner => &[Phase { rate: 0.193, months: None }],
+		}
+	}
+
+	/// Worker's rate, if reduced as well.
+	pub fn worker_rate(&self) -> Option<TaxRate> {
+		match self {
+			Self::OldAgePensioner => Some(0.075),
+			Self::DisabilityPensioner => Some(0.089),
+			_ => None,
+		}
+	}
+
+	/// Whether the company's rates run out.
+	pub fn expires(&self) -> bool { self.phases().iter().any(|phase| phase.months.is_some()) }
+
+	/// Company's rate `months` after the month of hiring, while the incentive
+	/// lasts.
+	pub fn company_rate(&self, months: u32) -> Option<TaxRate> {
+		let mut start = 0;
+		for phase in self.phases() {
+			match phase.months {
+				Some(duration) if months >= start + duration => start += duration,
+				_ => return Some(phase.rate),
+			}
+		}
+		None
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn duration() {
+		assert_eq!(Incentive::FirstJob.company_rate(0), Some(0.11875));
+		assert_eq!(Incentive::FirstJob.company_rate(59), Some(0.11875));
+		assert_eq!(Incentive::FirstJob.company_rate(60), None);
+		assert_eq!(Incentive::VeryLongTermUnemployed.company_rate(35), Some(0.0));
+		assert_eq!(Incentive::Disability.company_rate(600), Some(0.119));
+		assert!(!Incentive::OldAgePensioner.expires());
+		assert_eq!(Incentive::OldAgePensioner.worker_rate(), Some(0.075));
+	}
+}
@@ -0,0 +1,202 @@
+//! Contributions of independent workers (Código Contributivo, art. 162.º to
+//! 168.º).
+
+use super::{limites, SegSocVarCatB};
+use crate::{
+	tax_year::TaxYear,
+	units::{Date, Money, Rounding},
+	Error, Result,
+};
+
+/// Income declared for a quarter.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuarterlyDeclaration {
+	pub services: Money,
+	/// Sales of goods, and hotel and restaurant services.
+	pub goods: Money,
+}
+
+impl QuarterlyDeclaration {
+	/// Rendimento relevante: 70% of services and 20% of goods.
+	pub fn relevant_income(&self) -> Money {
+		(self.services * 0.7 + self.goods * 0.2).round(Rounding::HalfUp)
+	}
+}
+
+/// An independent worker's declarations over a year.
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct IndependentWorker {
+	pub year: u32,
+	/// Income of each quarter of `year`, declared in the month after it.
+	pub declarations: [QuarterlyDeclaration; 4],
+	pub variation: SegSocVarCatB,
+	/// First day of activity, as the first 12 months are exempt.
+	pub start: Option<Date>,
+	/// Also employed by someone else, and contributing as such.
+	pub employed: bool,
+}
+
+/// Contribution of a month.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonthlyContribution {
+	pub year: u32,
+	pub month: u32,
+	/// Base de incidência: a third of the quarter's relevant income, varied as
+	/// chosen and capped at 12 IAS.
+	pub base: Money,
+	pub contribution: Money,
+}
+
+/// Contributions due in the three months after a declared quarter.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct QuarterlyContributions {
+	/// Quarter declared, from 1 to 4.
+	pub quarter: u32,
+	pub relevant_income: Money,
+	pub months: [MonthlyContribution; 3],
+}
+
+impl QuarterlyContributions {
+	pub fn total(&self) -> Money { self.months.iter().map(|month| month.contribution).sum() }
+}
+
+impl IndependentWorker {
+	fn exempt(&self, year: u32, month: u32) -> bool {
+		self.start.is_some_and(|start| {
+			let months = (year * 12 + month) as i64 - (start.year() * 12 + start.month()) as i64;
+			months < 12
+		})
+	}
+
+	/// Contributions due on each declared quarter. The fourth quarter's are
+	/// due in the first months of the next year, with the next year's IAS, or
+	/// with this year's while the next isn't known. Employees are exempt for
+	/// the whole year when their average monthly relevant income over the four
+	/// declarations stays under 4 IAS.
+	pub fn schedule(&self) -> Result<[QuarterlyContributions; 4]> {
+		if let Some(declaration) = (self.declarations.iter())
+			.find(|declaration| declaration.services < Money::zero() || declaration.goods < Money::zero())
+		{
+			return Err(Error::InvalidIncome(declaration.services.min(declaration.goods)));
+		}
+		let yearly_income: Money = self.declarations.iter().map(QuarterlyDeclaration::relevant_income).sum();
+		let employed_exempt = self.employed
+			&& yearly_income / 12.0 < limites::isencao_cat_a_cat_b(TaxYear::get(self.year)?).quantity();
+		let quarter = |quarter: u32| -> Result<QuarterlyContributions> {
+			let relevant_income = self.declarations[quarter as usize - 1].relevant_income();
+			let month = |i: u32| -> Result<MonthlyContribution> {
+				let (year, month) = match quarter {
+					4 => (self.year + 1, i),
+					_ => (self.year, quarter * 3 + i),
+				};
+				let tax_year = match TaxYear::get(year) {
+					Err(Error::UnsupportedYear(_)) if quarter == 4 => TaxYear::get(self.year)?,
+					tax_year => tax_year?,
+				};
+				let monthly = relevant_income / 3.0;
+				let cap = limites::base_incidencia(tax_year).quantity();
+				let base = (monthly * self.variation.factor()).round(Rounding::HalfUp).min(cap);
+				let contribution = match self.exempt(year, month) || employed_exempt {
+					true => Money::zero(),
+					false => (base * tax_year.ss.independente)
+						.round(Rounding::HalfUp)
+						.max(tax_year.independent_minimum_contribution),
+				};
+				Ok(MonthlyContribution { year, month, base, contribution })
+			};
+			Ok(QuarterlyContributions { quarter, relevant_income, months: [month(1)?, month(2)?, month(3)?] })
+		};
+		Ok([quarter(1)?, quarter(2)?, quarter(3)?, quarter(4)?])
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn worker(services: f64) -> IndependentWorker {
+		let declaration = QuarterlyDeclaration { services: Money::new(services), goods: Money::zero() };
+		IndependentWorker { year: 2022, declarations: [declaration; 4], ..Default::default() }
+	}
+
+	#[test]
+	fn contributions() {
+		let schedule = worker(6000.0).schedule().unwrap();
+		// 70% of 6000€ over 3 months, at 21.4%
+		assert_eq!(schedule[0].relevant_income, Money::new(4200.0));
+		assert_eq!(schedule[0].months[0].month, 4);
+		assert_eq!(schedule[0].months[0].base, Money::new(1400.0));
+		assert_eq!(schedule[0].months[0].contribution, Money::new(299.6));
+		assert_eq!(schedule[0].total(), Money::new(898.8));
+		// The fourth quarter is paid the next year
+		assert_eq!((schedule[3].months[0].year, schedule[3].months[0].month), (2023, 1));
+		// Goods count for 20%
+		let declaration = QuarterlyDeclaration { services: Money::zero(), goods: Money::new(15_000.0) };
+		assert_eq!(declaration.relevant_income(), Money::new(3000.0));
+	}
+
+	#[test]
+	fn variation_minimum_and_cap() {
+		let varied = IndependentWorker { variation: SegSocVarCatB::M25, ..worker(6000.0) };
+		assert_eq!(varied.schedule().unwrap()[0].months[0].base, Money::new(1050.0));
+		assert_eq!(worker(0.0).schedule().unwrap()[0].months[0].contribution, Money::new(20.0));
+		// 12 IAS of the month's year
+		let schedule = worker(100_000.0).schedule().unwrap();
+		assert_eq!(schedule[0].months[0].base, Money::new(443.20 * 12.0));
+		assert_eq!(schedule[3].months[0].base, Money::new(480.43 * 12.0));
+		// Until the next year's IAS is known
+		let schedule = IndependentWorker { year: 2026, ..worker(100_000.0) }.schedule().unwrap();
+		assert_eq!(schedule[3].months[0].year, 2027);
+		assert_eq!(schedule[3].months[0].base, Money::new(537.13 * 12.0));
+	}
+
+	#[test]
+	fn first_year_exemption() {
+		let worker = IndependentWorker { start: Some("2022-03-15".parse().unwrap()), ..worker(6000.0) };
+		let schedule = worker.schedule().unwrap();
+		assert!(schedule[..3].iter().all(|quarter| quarter.total() == Money::zero()));
+		// Exempt until February
+		assert_eq!(schedule[3].months[1].contribution, Money::zero());
+		assert_eq!(schedule[3].months[2].contribution, Money::new(299.6));
+		let invalid = IndependentWorker {
+			declarations: [QuarterlyDeclaration { services: Money::new(-1.0), goods: Money::zero() }; 4],
+			..worker
+		};
+		assert!(matches!(invalid.schedule(), Err(Error::InvalidIncome(_))));
+	}
+
+	#[test]
+	fn employed() {
+		// 1400€ a month on average is under 4 IAS (1772.80€)
+		let low = IndependentWorker { employed: true, ..worker(6000.0) };
+		let schedule = low.schedule().unwrap();
+		assert!(schedule.iter().all(|quarter| quarter.total() == Money::zero()));
+		// 2100€ a month isn't
+		let high = IndependentWorker { employed: true, ..worker(9000.0) };
+		assert_eq!(high.schedule().unwrap()[0].months[0].contribution, Money::new(449.4));
+		// Averaged over the year, so a strong quarter alone doesn't count
+		let quarter =
+			|services: f64| QuarterlyDeclaration { services: Money::new(services), goods: Money::zero() };
+		let uneven = IndependentWorker {
+			declarations: [quarter(12_000.0), quarter(0.0), quarter(0.0), quarter(3000.0)],
+			..low
+		};
+		assert!(uneven.schedule().unwrap().iter().all(|quarter| quarter.total() == Money::zero()));
+		// 2100€, 2100€, 2100€ and 21000€ are 2275€ a month on average
+		let uneven = IndependentWorker {
+			declarations: [quarter(3000.0), quarter(3000.0), quarter(3000.0), quarter(30_000.0)],
+			..low
+		};
+		let schedule = uneven.schedule().unwrap();
+		assert_eq!(schedule[0].months[0].contribution, Money::new(149.8));
+		// Capped at 12 IAS of 2023
+		assert_eq!(schedule[3].months[0].contribution, Money::new(1233.74));
+		let tax_year = TaxYear::get(2023).unwrap();
+		assert_eq!(limites::isencao_cat_a_cat_b(tax_year).quantity(), Money::new(1921.72));
+		assert_eq!(limites::serv_min_entidade_contratante(tax_year).quantity(), Money::new(2882.58));
+	}
+}
@@ -0,0 +1,128 @@
+pub mod entidade_contratante;
+pub mod incentives;
+pub mod independent;
+
+pub mod tax {
+	use crate::TaxRate;
+	pub const TRABALHADOR: TaxRate = 0.11;
+	pub const EMPRESA: TaxRate = 0.2375;
+	pub const FUNDO_GARANTIA_SALARIAL: TaxRate = 0.01;
+	pub const INDEPENDENTE: TaxRate = 0.214;
+	pub const CAT_B: TaxRate = 0.7 * INDEPENDENTE;
+	pub const CAT_A: TaxRate = TRABALHADOR + EMPRESA;
+	pub const MEMBRO_ORGAO_ESTATUTARIO: TaxRate = 0.093;
+	pub const EMPRESA_ORGAO_ESTATUTARIO: TaxRate = 0.203;
+}
+
+use derive_builder::Builder;
+
+use crate::TaxRate;
+
+/// Contribution rates of the general regime.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rates {
+	pub trabalhador: TaxRate,
+	pub empresa: TaxRate,
+	pub cat_b: TaxRate,
+	/// On the relevant income of independent workers.
+	pub independente: TaxRate,
+}
+
+pub const RATES: Rates = Rates {
+	trabalhador: tax::TRABALHADOR,
+	empresa: tax::EMPRESA,
+	cat_b: tax::CAT_B,
+	independente: tax::INDEPENDENTE,
+};
+
+#[derive(Builder, Debug, Default, Clone, PartialEq)]
+pub struct Taxes {
+	#[builder(default = "0.01")]
+	pub salary_guarantee_fund_tax: TaxRate,
+	#[builder(default = "0.2375")]
+	pub company_tsu: TaxRate,
+	#[builder(default = "0.11")]
+	pub worker_tsu: TaxRate,
+	#[builder(default = "0.05")]
+	pub unimputed_travel_expenses_tax: TaxRate,
+	#[builder(default = "0.0015")]
+	pub work_accident_fund_tax: TaxRate,
+	#[builder(default = "0.04")]
+	pub insurance_stamp_tax: TaxRate,
+	#[builder(default = "0.025")]
+	pub insurance_inem_tax: TaxRate,
+}
+
+/// How an employee contributes.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Regime {
+	#[default]
+	TrabalhadorPorContaDeOutrem,
+	/// Member of a statutory body, such as a gerente or administrador (art.
+	/// 76.º to 78.º). Members with management functions contribute like
+	/// employees, and the others at 9.3%, with the company at 20.3%. There's
+	/// no salary guarantee fund, and contributions are due on at least an IAS.
+	MembroOrgaoEstatutario { gerencia: bool },
+}
+
+impl Regime {
+	/// Company's and member's rates, if this regime doesn't follow the
+	/// general one.
+	pub fn rates(&self) -> Option<(TaxRate, TaxRate)> {
+		match self {
+			Self::TrabalhadorPorContaDeOutrem | Self::MembroOrgaoEstatutario { gerencia: true } => None,
+			Self::MembroOrgaoEstatutario { gerencia: false } => {
+				Some((tax::EMPRESA_ORGAO_ESTATUTARIO, tax::MEMBRO_ORGAO_ESTATUTARIO))
+			}
+		}
+	}
+}
+
+/// Limits of the Código Contributivo, in IAS of the year.
+pub mod limites {
+	use crate::{
+		tax_year::TaxYear,
+		units::{MoneyRate, Monthly, Yearly},
+	};
+
+	/// Average monthly relevant income under which independent workers who
+	/// are also employed don't contribute (art. 157.º).
+	pub fn isencao_cat_a_cat_b(tax_year: &TaxYear) -> MoneyRate<Monthly> {
+		MoneyRate::new(tax_year.ias.quantity() * 4.0, Monthly::M12)
+	}
+
+	/// Most the monthly base de incidência of independent workers can be
+	/// (art. 163.º).
+	pub fn base_incidencia(tax_year: &TaxYear) -> MoneyRate<Monthly> {
+		MoneyRate::new(tax_year.ias.quantity() * 12.0, Monthly::M12)
+	}
+
+	/// Services over a year from which the independent worker's contracting
+	/// entity also contributes (art. 140.º).
+	pub fn serv_min_entidade_contratante(tax_year: &TaxYear) -> MoneyRate<Yearly> {
+		MoneyRate::new(tax_year.ias.quantity() * 6.0, Yearly)
+	}
+}
+
+/// Variation of the relevant income an independent worker may choose.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SegSocVarCatB {
+	M25 = -25,
+	M20 = -20,
+	M15 = -15,
+	M10 = -10,
+	M05 = -5,
+	#[default]
+	Zero = 0,
+	P05 = 5,
+	P10 = 10,
+	P15 = 15,
+	P20 = 20,
+	P25 = 25,
+}
+
+impl SegSocVarCatB {
+	pub fn factor(&self) -> f64 { 1.0 + *self as i32 as f64 / 100.0 }
+}
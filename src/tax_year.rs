@@ -0,0 +1,248 @@
+use crate::{
+	irs::{
+		brackets::{self, Bracket, BracketTable},
+		jovem,
+		settlement::MinimumExistence,
+		withholding::{self, RetentionTables},
+	},
+	ss,
+	units::{Locale, Money, MoneyRate, Monthly, TaxRate, Workdaily, Yearly},
+	Error, Result,
+};
+
+/// Amounts that aren't taxed, or that aren't subject to withholding, up to a
+/// limit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NonTaxationLimits {
+	pub subsidio_refeicao: MoneyRate<Workdaily>,
+	pub vale_refeicao: MoneyRate<Workdaily>,
+	pub ajudas_custo_km: Money,
+	pub ajudas_custo_dia: MoneyRate<Workdaily>,
+	pub isencao_retencao_cat_b: MoneyRate<Yearly>,
+}
+
+impl NonTaxationLimits {
+	const fn new(subsidio_refeicao: f64, vale_refeicao: f64, isencao_retencao_cat_b: f64) -> Self {
+		Self {
+			subsidio_refeicao: MoneyRate::new_const(Money::new(subsidio_refeicao), Workdaily),
+			vale_refeicao: MoneyRate::new_const(Money::new(vale_refeicao), Workdaily),
+			ajudas_custo_km: Money::new(0.36),
+			ajudas_custo_dia: MoneyRate::new_const(Money::new(50.20), Workdaily),
+			isencao_retencao_cat_b: MoneyRate::new_const(Money::new(isencao_retencao_cat_b), Yearly),
+		}
+	}
+}
+
+/// Tax and social security parameters in force in a year.
+#[derive(Debug)]
+pub struct TaxYear {
+	pub year: u32,
+	/// Indexante dos Apoios Sociais.
+	pub ias: MoneyRate<Monthly>,
+	/// Retribuição mínima mensal garantida.
+	pub minimum_wage: MoneyRate<Monthly>,
+	pub brackets: &'static [Bracket],
+	/// Dedução específica of category A income (CIRS, art. 25.º).
+	pub specific_deduction: Money,
+	/// Most that can be deducted from the tax for rents (CIRS, art. 78.º-E).
+	pub rent_deduction_limit: Money,
+	/// Taxable income from which the deductions from the tax are capped at the
+	/// least (CIRS, art. 78.º, n.º 7).
+	pub deductions_cap_income: Money,
+	/// Withholding tables, in the order they came into force in the year.
+	pub withholding: &'static [RetentionTables],
+	/// Rate withheld on category B income from the professions of art. 151.º
+	/// (CIRS, art. 101.º).
+	pub cat_b_withholding: TaxRate,
+	pub minimum_existence: MinimumExistence,
+	pub irs_jovem: Option<&'static jovem::Rules>,
+	pub non_taxation_limits: NonTaxationLimits,
+	pub ss: ss::Rates,
+	/// Least contribution of an independent worker in a month (Código
+	/// Contributivo, art. 168.º).
+	pub independent_minimum_contribution: Money,
+}
+
+impl TaxYear {
+	pub fn get(year: u32) -> Result<&'static Self> {
+		YEARS.iter().find(|tax_year| tax_year.year == year).ok_or(Error::UnsupportedYear(year))
+	}
+
+	/// Valor de referência of the mínimo de existência: the larger of 1.5 ×
+	/// 14 × IAS and the yearly minimum wage (CIRS, art. 70.º).
+	pub fn minimum_existence_reference(&self) -> Money {
+		(self.ias.as_yearly().quantity() * 1.5).max(self.minimum_wage.as_yearly().quantity())
+	}
+
+	pub fn bracket_table(&self, locale: Locale) -> BracketTable<'static> {
+		BracketTable { brackets: self.brackets, locale }
+	}
+
+	/// Withholding tables in force in `month`.
+	pub fn withholding_tables(&self, month: u32) -> Result<&'static RetentionTables> {
+		(self.withholding.iter().rev().find(|tables| tables.from_month() <= month))
+			.ok_or_else(|| Error::MissingTable(format!("withholding tables for {}/{}", month, self.year)))
+	}
+}
+
+const fn monthly(value: f64) -> MoneyRate<Monthly> { MoneyRate::new_const(Money::new(value), Monthly::M14) }
+
+static YEARS: [TaxYear; 8] = [
+	TaxYear {
+		year: 2019,
+		ias: monthly(435.76),
+		minimum_wage: monthly(600.0),
+		brackets: &brackets::year_2019,
+		specific_deduction: Money::new(4104.0),
+		rent_deduction_limit: Money::new(502.0),
+		deductions_cap_income: Money::new(80_640.0),
+		withholding: &withholding::year_2019::tables,
+		cat_b_withholding: 0.25,
+		minimum_existence: MinimumExistence::NetIncomeFloor,
+		irs_jovem: None,
+		non_taxation_limits: NonTaxationLimits::new(4.77, 7.63, 10_000.0),
+		ss: ss::RATES,
+		independent_minimum_contribution: Money::new(20.0),
+	},
+	TaxYear {
+		year: 2020,
+		ias: monthly(438.81),
+		minimum_wage: monthly(635.0),
+		brackets: &brackets::year_2021,
+		specific_deduction: Money::new(4104.0),
+		rent_deduction_limit: Money::new(502.0),
+		deductions_cap_income: Money::new(80_882.0),
+		withholding: &withholding::year_2020::tables,
+		cat_b_withholding: 0.25,
+		minimum_existence: MinimumExistence::NetIncomeFloor,
+		irs_jovem: Some(&jovem::year_2020),
+		non_taxation_limits: NonTaxationLimits::new(4.77, 7.63, 10_000.0),
+		ss: ss::RATES,
+		independent_minimum_contribution: Money::new(20.0),
+	},
+	TaxYear {
+		year: 2021,
+		ias: monthly(438.81),
+		minimum_wage: monthly(665.0),
+		brackets: &brackets::year_2021,
+		specific_deduction: Money::new(4104.0),
+		rent_deduction_limit: Money::new(502.0),
+		deductions_cap_income: Money::new(80_882.0),
+		withholding: &withholding::year_2021::tables,
+		cat_b_withholding: 0.25,
+		minimum_existence: MinimumExistence::NetIncomeFloor,
+		irs_jovem: Some(&jovem::year_2020),
+		non_taxation_limits: NonTaxationLimits::new(4.77, 7.63, 10_000.0),
+		ss: ss::RATES,
+		independent_minimum_contribution: Money::new(20.0),
+	},
+	TaxYear {
+		year: 2022,
+		ias: monthly(443.20),
+		minimum_wage: monthly(705.0),
+		brackets: &brackets::year_2022,
+		specific_deduction: Money::new(4104.0),
+		rent_deduction_limit: Money::new(502.0),
+		deductions_cap_income: Money::new(80_882.0),
+		withholding: &withholding::year_2022::tables,
+		cat_b_withholding: 0.25,
+		minimum_existence: MinimumExistence::NetIncomeFloor,
+		irs_jovem: Some(&jovem::year_2022),
+		non_taxation_limits: NonTaxationLimits::new(4.77, 7.63, 10_000.0),
+		ss: ss::RATES,
+		independent_minimum_contribution: Money::new(20.0),
+	},
+	TaxYear {
+		year: 2023,
+		ias: monthly(480.43),
+		minimum_wage: monthly(760.0),
+		brackets: &brackets::year_2023,
+		specific_deduction: Money::new(4104.0),
+		rent_deduction_limit: Money::new(502.0),
+		deductions_cap_income: Money::new(80_882.0),
+		withholding: &withholding::year_2023::tables,
+		cat_b_withholding: 0.25,
+		minimum_existence: MinimumExistence::TaxableIncomeDeduction,
+		irs_jovem: Some(&jovem::year_2023),
+		non_taxation_limits: NonTaxationLimits::new(5.20, 8.32, 10_000.0),
+		ss: ss::RATES,
+		independent_minimum_contribution: Money::new(20.0),
+	},
+	TaxYear {
+		year: 2024,
+		ias: monthly(509.26),
+		minimum_wage: monthly(820.0),
+		brackets: &brackets::year_2024,
+		specific_deduction: Money::new(4104.0),
+		rent_deduction_limit: Money::new(600.0),
+		deductions_cap_income: Money::new(81_199.0),
+		withholding: &withholding::year_2024::tables,
+		cat_b_withholding: 0.25,
+		minimum_existence: MinimumExistence::TaxableIncomeDeduction,
+		irs_jovem: Some(&jovem::year_2024),
+		non_taxation_limits: NonTaxationLimits::new(6.00, 9.60, 12_500.0),
+		ss: ss::RATES,
+		independent_minimum_contribution: Money::new(20.0),
+	},
+	TaxYear {
+		year: 2025,
+		ias: monthly(522.50),
+		minimum_wage: monthly(870.0),
+		brackets: &brackets::year_2025,
+		specific_deduction: Money::new(4462.15),
+		rent_deduction_limit: Money::new(700.0),
+		deductions_cap_income: Money::new(83_696.0),
+		withholding: &withholding::year_2025::tables,
+		cat_b_withholding: 0.23,
+		minimum_existence: MinimumExistence::TaxableIncomeDeduction,
+		irs_jovem: Some(&jovem::year_2025),
+		non_taxation_limits: NonTaxationLimits::new(6.00, 10.20, 15_000.0),
+		ss: ss::RATES,
+		independent_minimum_contribution: Money::new(20.0),
+	},
+	TaxYear {
+		year: 2026,
+		ias: monthly(537.13),
+		minimum_wage: monthly(920.0),
+		brackets: &brackets::year_2026,
+		specific_deduction: Money::new(4587.09),
+		rent_deduction_limit: Money::new(800.0),
+		deductions_cap_income: Money::new(86_634.0),
+		withholding: &withholding::year_2026::tables,
+		cat_b_withholding: 0.23,
+		minimum_existence: MinimumExistence::TaxableIncomeDeduction,
+		irs_jovem: Some(&jovem::year_2025),
+		non_taxation_limits: NonTaxationLimits::new(6.15, 10.46, 15_000.0),
+		ss: ss::RATES,
+		independent_minimum_contribution: Money::new(20.0),
+	},
+];
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn registry() {
+		for year in 2019..=2026 {
+			let tax_year = TaxYear::get(year).unwrap();
+			assert_eq!(tax_year.year, year);
+			assert!(tax_year.brackets.last().unwrap().rate().is_none());
+			for pair in tax_year.brackets.windows(2) {
+				assert!(pair[1].rate().is_none_or(|upper| pair[0].rate().unwrap() < upper));
+				assert!(pair[0].tax() < pair[1].tax());
+			}
+		}
+		assert!(matches!(TaxYear::get(2018), Err(Error::UnsupportedYear(2018))));
+		assert_eq!(TaxYear::get(2022).unwrap().ias.quantity(), Money::new(443.20));
+		assert_eq!(TaxYear::get(2022).unwrap().minimum_existence_reference(), Money::new(9870.0));
+		assert_eq!(TaxYear::get(2019).unwrap().minimum_existence_reference(), Money::new(9150.96));
+		for year in 2019..=2026 {
+			assert!(TaxYear::get(year).unwrap().withholding_tables(1).is_ok());
+		}
+		let tax_year = TaxYear::get(2023).unwrap();
+		assert_eq!(tax_year.withholding_tables(6).unwrap().from_month(), 1);
+		assert_eq!(tax_year.withholding_tables(7).unwrap().from_month(), 7);
+		assert_eq!(tax_year.withholding_tables(12).unwrap().from_month(), 7);
+	}
+}
@@ -0,0 +1,269 @@
+use std::ops::RangeInclusive;
+
+use crate::{
+	salary::Context,
+	units::{Money, MoneyRate, Rounding, TaxRate, Yearly, YearlyPlan},
+	Error, Result,
+};
+
+/// How a subsidy is paid: a lump sum in `month`, except for the share paid in
+/// duodécimos (twelfths) along with every monthly salary.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SubsidyPayment {
+	pub month: u32,
+	/// Share of the subsidy paid in twelfths, from 0 (none) to 1 (all of it).
+	pub twelfths: f64,
+}
+
+impl SubsidyPayment {
+	pub const fn new(month: u32, twelfths: f64) -> Self { Self { month, twelfths } }
+
+	/// The whole subsidy paid in `month`.
+	pub const fn in_month(month: u32) -> Self { Self::new(month, 0.0) }
+
+	/// The whole subsidy paid in twelfths.
+	pub const fn in_twelfths() -> Self { Self::new(12, 1.0) }
+
+	fn validate(&self) -> Result<()> {
+		if !(1..=12).contains(&self.month) {
+			return Err(Error::InvalidContext(format!("subsidy paid in month {}", self.month)));
+		}
+		if !(0.0..=1.0).contains(&self.twelfths) {
+			return Err(Error::InvalidRate("subsidy paid in twelfths", self.twelfths));
+		}
+		Ok(())
+	}
+
+	// Twelfths are rounded to the cent and paid in the months employed, the
+	// last of which takes the difference so that nothing is lost over the
+	// year. The lump sum is paid in the last month if the contract ends
+	// before `month`, or in the first if it starts after.
+	fn split(&self, subsidy: Money, month: u32, months: &RangeInclusive<u32>) -> SubsidySplit {
+		if !months.contains(&month) {
+			return (Money::zero(), Money::zero());
+		}
+		let subsidy = subsidy.round(Rounding::HalfUp);
+		let in_twelfths = (subsidy * self.twelfths).round(Rounding::HalfUp);
+		let count = (months.end() - months.start() + 1) as f64;
+		let twelfth = (in_twelfths / count).round(Rounding::HalfUp);
+		let twelfth = if month == *months.end() { in_twelfths - twelfth * (count - 1.0) } else { twelfth };
+		let paid_in = self.month.clamp(*months.start(), *months.end());
+		let lump = if month == paid_in { subsidy - in_twelfths } else { Money::zero() };
+		(twelfth, lump)
+	}
+}
+
+/// When each payment of the year is made.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PaymentSchedule {
+	/// Month spent on vacation, when headings paid 11 times a year aren't paid.
+	pub vacation_month: u32,
+	pub vacation_subsidy: SubsidyPayment,
+	pub christmas_subsidy: SubsidyPayment,
+}
+
+impl PaymentSchedule {
+	pub fn validate(&self) -> Result<()> {
+		if !(1..=12).contains(&self.vacation_month) {
+			return Err(Error::InvalidContext(format!("vacation in month {}", self.vacation_month)));
+		}
+		self.vacation_subsidy.validate()?;
+		self.christmas_subsidy.validate()
+	}
+}
+
+impl Default for PaymentSchedule {
+	fn default() -> Self {
+		Self {
+			vacation_month: 8,
+			vacation_subsidy: SubsidyPayment::in_month(6),
+			christmas_subsidy: SubsidyPayment::in_month(12),
+		}
+	}
+}
+
+/// Twelfths and lump sum of a subsidy paid in a month.
+type SubsidySplit = (Money, Money);
+
+/// Regular payment of `plan` in `month`, and the split of each subsidy.
+fn split_month(
+	plan: &YearlyPlan<Money>, month: u32, employed: &RangeInclusive<u32>, ctx: &Context,
+) -> Result<(Money, SubsidySplit, SubsidySplit)> {
+	let schedule = &ctx.payment_schedule;
+	let regular = if month == schedule.vacation_month { plan.vacation } else { plan.regular };
+	let mut regular = (regular * ctx.share_of_month(month)?).round(Rounding::HalfUp);
+	if month == *employed.end() {
+		// Vacation earned but not taken is paid when the contract ends
+		regular += (plan.vacation * ctx.untaken_vacation_share()).round(Rounding::HalfUp);
+	}
+	let vacation_subsidy = plan.bonus * ctx.vacation_subsidy_share();
+	let christmas_subsidy = plan.bonus * ctx.christmas_subsidy_share();
+	let vacation = schedule.vacation_subsidy.split(vacation_subsidy, month, employed);
+	let christmas = schedule.christmas_subsidy.split(christmas_subsidy, month, employed);
+	Ok((regular, vacation, christmas))
+}
+
+/// What's paid in a month, and what's deducted from it.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MonthlyPayment {
+	pub month: u32,
+	pub regular: Money,
+	/// Twelfths and lump sum of the vacation subsidy.
+	pub vacation_subsidy: Money,
+	/// Twelfths and lump sum of the Christmas subsidy.
+	pub christmas_subsidy: Money,
+	pub ss: Money,
+	pub irs: Money,
+}
+
+impl MonthlyPayment {
+	pub fn gross(&self) -> Money { self.regular + self.vacation_subsidy + self.christmas_subsidy }
+
+	pub fn net(&self) -> Money { self.gross() - self.ss - self.irs }
+}
+
+/// Month-by-month cash flow of a salary.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Timeline {
+	pub months: Vec<MonthlyPayment>,
+}
+
+impl Timeline {
+	/// Lays out the payments in `gross` over the months employed, following
+	/// the context's payment schedule. SS contributions are due on the whole
+	/// month, but IRS is withheld separately from each subsidy lump sum (CIRS,
+	/// art. 99.º-C), while twelfths are withheld at the rate of the month's
+	/// salary.
+	pub fn new(
+		gross: &YearlyPlan<Money>, ss_taxable: &YearlyPlan<Money>, irs_taxable: &YearlyPlan<Money>,
+		ctx: &Context,
+	) -> Result<Self> {
+		#[allow(clippy::reversed_empty_ranges)]
+		let employed = ctx.months_employed().unwrap_or(1..=0);
+		let parts = |plan: &YearlyPlan<Money>, month: u32| split_month(plan, month, &employed, ctx);
+		let withhold = |month: u32, amount: Money, rate_of: Money| -> Result<Money> {
+			Ok((amount * ctx.irs_withholding(month, rate_of)?).round(Rounding::Truncate))
+		};
+		let months = (1..=12)
+			.map(|month| {
+				let (regular, vacation, christmas) = parts(gross, month)?;
+				let (ss_regular, ss_vacation, ss_christmas) = parts(ss_taxable, month)?;
+				let ss_taxable = ss_regular + ss_vacation.0 + ss_vacation.1 + ss_christmas.0 + ss_christmas.1;
+				let (irs_regular, irs_vacation, irs_christmas) = parts(irs_taxable, month)?;
+				let irs = withhold(month, irs_regular + irs_vacation.0 + irs_christmas.0, irs_regular)?
+					+ withhold(month, irs_vacation.1, irs_vacation.1)?
+					+ withhold(month, irs_christmas.1, irs_christmas.1)?;
+				Ok(MonthlyPayment {
+					month,
+					regular,
+					vacation_subsidy: vacation.0 + vacation.1,
+					christmas_subsidy: christmas.0 + christmas.1,
+					ss: (ctx.ss_base(month, ss_taxable)? * ctx.worker_ss_rate()?).round(Rounding::HalfUp),
+					irs,
+				})
+			})
+			.collect::<Result<_>>()?;
+		Ok(Self { months })
+	}
+
+	/// SS contributions on `ss_taxable` over the months employed, at the rate
+	/// of each month.
+	pub(crate) fn contributions<F: Fn(u32) -> Result<TaxRate>>(
+		ss_taxable: &YearlyPlan<Money>, ctx: &Context, rate: F,
+	) -> Result<MoneyRate<Yearly>> {
+		#[allow(clippy::reversed_empty_ranges)]
+		let employed = ctx.months_employed().unwrap_or(1..=0);
+		let total = (1..=12)
+			.map(|month| {
+				let (regular, vacation, christmas) = split_month(ss_taxable, month, &employed, ctx)?;
+				let taxable = regular + vacation.0 + vacation.1 + christmas.0 + christmas.1;
+				Ok((ctx.ss_base(month, taxable)? * rate(month)?).round(Rounding::HalfUp))
+			})
+			.sum::<Result<Money>>()?;
+		Ok(MoneyRate::new(total, Yearly))
+	}
+
+	/// Payment of `month`, from 1 to 12.
+	pub fn month(&self, month: u32) -> Option<&MonthlyPayment> {
+		self.months.get((month as usize).checked_sub(1)?)
+	}
+
+	pub fn gross(&self) -> MoneyRate<Yearly> { self.total(MonthlyPayment::gross) }
+
+	pub fn ss(&self) -> MoneyRate<Yearly> { self.total(|m| m.ss) }
+
+	pub fn irs(&self) -> MoneyRate<Yearly> { self.total(|m| m.irs) }
+
+	pub fn net(&self) -> MoneyRate<Yearly> { self.total(MonthlyPayment::net) }
+
+	fn total<F: Fn(&MonthlyPayment) -> Money>(&self, f: F) -> MoneyRate<Yearly> {
+		MoneyRate::new(self.months.iter().map(f).sum::<Money>(), Yearly)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::salary::{ContextBuilder, MealAllowance, Salary};
+
+	#[test]
+	fn subsidies_in_their_months() {
+		let ctx = Context::default();
+		let salary = Salary::new(1000.0, MealAllowance::None, 0.0);
+		let timeline = salary.timeline(&ctx).unwrap();
+		assert_eq!(timeline.month(1).unwrap().gross(), Money::new(1000.0));
+		assert_eq!(timeline.month(6).unwrap().vacation_subsidy, Money::new(1000.0));
+		assert_eq!(timeline.month(12).unwrap().christmas_subsidy, Money::new(1000.0));
+		// 11% SS and 11.3% IRS, each subsidy withheld on its own
+		assert_eq!(timeline.month(12).unwrap().net(), Money::new(2000.0 - 220.0 - 226.0));
+		assert_eq!(timeline.gross(), MoneyRate::new(14000.0, Yearly));
+		assert_eq!(timeline.net(), salary.yearly_plan_withhold_net(&ctx).unwrap().yearly_total());
+		assert_eq!(timeline.month(0), None);
+		assert_eq!(timeline.month(13), None);
+	}
+
+	#[test]
+	fn subsidies_in_twelfths() {
+		let payment_schedule = PaymentSchedule {
+			vacation_subsidy: SubsidyPayment::new(7, 0.5),
+			christmas_subsidy: SubsidyPayment::in_twelfths(),
+			..Default::default()
+		};
+		let ctx = ContextBuilder::default().payment_schedule(payment_schedule).build().unwrap();
+		let timeline = Salary::new(1000.0, MealAllowance::None, 0.0).timeline(&ctx).unwrap();
+		assert_eq!(timeline.month(1).unwrap().christmas_subsidy, Money::new(83.33));
+		assert_eq!(timeline.month(12).unwrap().christmas_subsidy, Money::new(83.37));
+		assert_eq!(timeline.month(1).unwrap().vacation_subsidy, Money::new(41.67));
+		assert_eq!(timeline.month(7).unwrap().vacation_subsidy, Money::new(541.67));
+		assert_eq!(timeline.gross(), MoneyRate::new(14000.0, Yearly));
+		// Twelfths are withheld at the rate of the salary they're paid with
+		assert_eq!(timeline.month(1).unwrap().irs, Money::new(1125.0 * 0.113).round(Rounding::Truncate));
+		// The 500€ lump sum falls under the 0% bracket
+		assert_eq!(timeline.month(7).unwrap().irs, Money::new(1125.0 * 0.113).round(Rounding::Truncate));
+	}
+
+	#[test]
+	fn hired_in_september() {
+		let start = "2022-09-01".parse().unwrap();
+		let ctx = ContextBuilder::default().start(start).build().unwrap();
+		let timeline = Salary::new(1000.0, MealAllowance::None, 0.0).timeline(&ctx).unwrap();
+		assert_eq!(timeline.month(8).unwrap().gross(), Money::zero());
+		assert_eq!(timeline.month(9).unwrap().regular, Money::new(1000.0));
+		// 4 complete months earn 8 of the 22 vacation days, paid with the first salary
+		assert_eq!(timeline.month(9).unwrap().vacation_subsidy, Money::new(363.64));
+		assert_eq!(timeline.month(12).unwrap().christmas_subsidy, Money::new(334.25));
+	}
+
+	#[test]
+	fn vacation_month_skips_headings_paid_11_times() {
+		let ctx = Context::default();
+		let salary = Salary::new(1000.0, MealAllowance::card(ctx.tax_year().unwrap()), 100.0);
+		let timeline = salary.timeline(&ctx).unwrap();
+		assert_eq!(timeline.month(8).unwrap().regular, Money::new(1000.0));
+		assert!(timeline.month(7).unwrap().regular > Money::new(1100.0));
+	}
+}
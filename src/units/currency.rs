@@ -0,0 +1,107 @@
+use std::{
+	fmt::Display,
+	ops::{Div, Mul},
+	str::FromStr,
+};
+
+use super::{Money, UnitsError};
+
+/// ISO 4217 currency code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Currency([u8; 3]);
+impl Currency {
+	pub const EUR: Currency = Currency(*b"EUR");
+	pub const USD: Currency = Currency(*b"USD");
+	pub const GBP: Currency = Currency(*b"GBP");
+	pub const CHF: Currency = Currency(*b"CHF");
+	pub const BRL: Currency = Currency(*b"BRL");
+
+	pub fn code(&self) -> &str { std::str::from_utf8(&self.0).expect("currency codes are ASCII") }
+}
+
+#[cfg(feature = "serde")]
+serde_via_str!(Currency);
+
+impl FromStr for Currency {
+	type Err = UnitsError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		match s.trim().as_bytes() {
+			&[a, b, c] if [a, b, c].iter().all(u8::is_ascii_uppercase) => Ok(Currency([a, b, c])),
+			_ => Err(UnitsError::InvalidCurrency(s.to_string())),
+		}
+	}
+}
+
+impl Display for Currency {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.pad(self.code()) }
+}
+
+/// An amount in some currency other than the euro. It can only be mixed with
+/// other amounts of the same currency, and must be converted to [`Money`]
+/// (euros) through an [`ExchangeRates`](super::ExchangeRates) table before
+/// being used in IRS or SS calculations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ForeignMoney {
+	amount: Money,
+	currency: Currency,
+}
+impl ForeignMoney {
+	pub fn new<M: Into<Money>>(amount: M, currency: Currency) -> Self {
+		Self { amount: amount.into(), currency }
+	}
+
+	pub const fn amount(&self) -> Money { self.amount }
+
+	pub const fn currency(&self) -> Currency { self.currency }
+
+	pub fn add(&self, rhs: &Self) -> Result<Self, UnitsError> {
+		self.check_currency(rhs)?;
+		Ok(Self { amount: self.amount + rhs.amount, currency: self.currency })
+	}
+
+	pub fn sub(&self, rhs: &Self) -> Result<Self, UnitsError> {
+		self.check_currency(rhs)?;
+		Ok(Self { amount: self.amount - rhs.amount, currency: self.currency })
+	}
+
+	fn check_currency(&self, rhs: &Self) -> Result<(), UnitsError> {
+		if self.currency == rhs.currency {
+			Ok(())
+		} else {
+			Err(UnitsError::CurrencyIsDifferent(self.currency, rhs.currency))
+		}
+	}
+}
+
+impl PartialOrd for ForeignMoney {
+	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+		if self.currency == other.currency {
+			self.amount.partial_cmp(&other.amount)
+		} else {
+			None
+		}
+	}
+}
+
+impl Display for ForeignMoney {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let amount = match f.precision() {
+			Some(precision) => format!("{:.precision$}", self.amount),
+			None => self.amount.to_string(),
+		};
+		write!(f, "{} {}", amount.trim_end_matches('€'), self.currency)
+	}
+}
+
+impl Mul<f64> for ForeignMoney {
+	type Output = Self;
+
+	fn mul(self, rhs: f64) -> Self::Output { Self { amount: self.amount * rhs, currency: self.currency } }
+}
+impl Div<f64> for ForeignMoney {
+	type Output = Self;
+
+	fn div(self, rhs: f64) -> Self::Output { Self { amount: self.amount / rhs, currency: self.currency } }
+}
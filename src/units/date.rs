@@ -0,0 +1,167 @@
+use std::{fmt::Display, str::FromStr};
+
+use super::UnitsError;
+
+/// A calendar day, without time or timezone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+	year: u32,
+	month: u32,
+	day: u32,
+}
+
+impl Date {
+	pub fn new(year: u32, month: u32, day: u32) -> Result<Self, UnitsError> {
+		if (1..=12).contains(&month) && day >= 1 && day <= Self::days_in_month(year, month) {
+			Ok(Self { year, month, day })
+		} else {
+			Err(UnitsError::InvalidDate(format!("{:04}-{:02}-{:02}", year, month, day)))
+		}
+	}
+
+	pub const fn year(&self) -> u32 { self.year }
+
+	pub const fn month(&self) -> u32 { self.month }
+
+	pub const fn day(&self) -> u32 { self.day }
+
+	pub const fn is_leap_year(year: u32) -> bool {
+		year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+	}
+
+	pub const fn days_in_month(year: u32, month: u32) -> u32 {
+		match month {
+			2 if Self::is_leap_year(year) => 29,
+			2 => 28,
+			4 | 6 | 9 | 11 => 30,
+			_ => 31,
+		}
+	}
+
+	pub const fn days_in_year(year: u32) -> u32 { if Self::is_leap_year(year) { 366 } else { 365 } }
+
+	pub fn first_of_year(year: u32) -> Self { Self { year, month: 1, day: 1 } }
+
+	pub fn last_of_year(year: u32) -> Self { Self { year, month: 12, day: 31 } }
+
+	pub fn weekday(&self) -> Weekday {
+		// 1970-01-01 was a Thursday
+		match (self.days_since_epoch() + 3).rem_euclid(7) {
+			0 => Weekday::Monday,
+			1 => Weekday::Tuesday,
+			2 => Weekday::Wednesday,
+			3 => Weekday::Thursday,
+			4 => Weekday::Friday,
+			5 => Weekday::Saturday,
+			_ => Weekday::Sunday,
+		}
+	}
+
+	pub fn add_days(&self, days: i64) -> Self { Self::from_days_since_epoch(self.days_since_epoch() + days) }
+
+	/// Days from `self` to `other`, negative if `other` comes first.
+	pub fn days_until(&self, other: &Self) -> i64 { other.days_since_epoch() - self.days_since_epoch() }
+
+	/// Every day from `self` to `last`, both included.
+	pub fn iter_through(&self, last: Self) -> impl Iterator<Item = Date> {
+		let first = *self;
+		(0..=first.days_until(&last)).map(move |offset| first.add_days(offset))
+	}
+
+	// Days since 1970-01-01 in the proleptic Gregorian calendar, after
+	// http://howardhinnant.github.io/date_algorithms.html
+	fn days_since_epoch(&self) -> i64 {
+		let (month, day) = (self.month as i64, self.day as i64);
+		let year = self.year as i64 - (month <= 2) as i64;
+		let era = year.div_euclid(400);
+		let year_of_era = year - era * 400;
+		let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+		let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+		era * 146_097 + day_of_era - 719_468
+	}
+
+	fn from_days_since_epoch(days: i64) -> Self {
+		let days = days + 719_468;
+		let era = days.div_euclid(146_097);
+		let day_of_era = days - era * 146_097;
+		let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+		let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+		let shifted_month = (5 * day_of_year + 2) / 153;
+		let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+		let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+		let year = year_of_era + era * 400 + (month <= 2) as i64;
+		Self { year: year as u32, month: month as u32, day: day as u32 }
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Weekday {
+	Monday,
+	Tuesday,
+	Wednesday,
+	Thursday,
+	Friday,
+	Saturday,
+	Sunday,
+}
+impl Weekday {
+	pub fn is_weekend(&self) -> bool { matches!(self, Self::Saturday | Self::Sunday) }
+}
+
+#[cfg(feature = "serde")]
+serde_via_str!(Date);
+
+impl FromStr for Date {
+	type Err = UnitsError;
+
+	/// Parses ISO 8601 dates, *i.e.* `YYYY-MM-DD`.
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let invalid = || UnitsError::InvalidDate(s.to_string());
+		let mut parts = s.trim().splitn(3, '-').map(|p| p.parse::<u32>().map_err(|_| invalid()));
+		let (Some(year), Some(month), Some(day)) = (parts.next(), parts.next(), parts.next()) else {
+			return Err(invalid());
+		};
+		Self::new(year?, month?, day?)
+	}
+}
+
+impl Display for Date {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parse_and_display() {
+		let date: Date = "2022-02-28".parse().unwrap();
+		assert_eq!(date, Date::new(2022, 2, 28).unwrap());
+		assert_eq!(date.to_string(), "2022-02-28");
+		assert!("2022-02-29".parse::<Date>().is_err());
+		assert!("2024-02-29".parse::<Date>().is_ok());
+		assert!("2022-13-01".parse::<Date>().is_err());
+		assert!("2022/01/01".parse::<Date>().is_err());
+	}
+
+	#[test]
+	fn arithmetic() {
+		let date = Date::new(2022, 12, 31).unwrap();
+		assert_eq!(date.add_days(1), Date::new(2023, 1, 1).unwrap());
+		assert_eq!(date.add_days(-365), Date::new(2021, 12, 31).unwrap());
+		assert_eq!(Date::new(2024, 2, 28).unwrap().add_days(1), Date::new(2024, 2, 29).unwrap());
+		assert_eq!(Date::new(2000, 3, 1).unwrap().add_days(-1), Date::new(2000, 2, 29).unwrap());
+		assert_eq!(Date::first_of_year(2024).days_until(&Date::last_of_year(2024)), 365);
+		assert_eq!(Date::first_of_year(2022).iter_through(Date::last_of_year(2022)).count(), 365);
+	}
+
+	#[test]
+	fn weekday() {
+		assert_eq!(Date::new(1970, 1, 1).unwrap().weekday(), Weekday::Thursday);
+		assert_eq!(Date::new(2022, 1, 1).unwrap().weekday(), Weekday::Saturday);
+		assert_eq!(Date::new(2024, 2, 29).unwrap().weekday(), Weekday::Thursday);
+		assert!(Date::new(1969, 12, 28).unwrap().weekday().is_weekend());
+	}
+}
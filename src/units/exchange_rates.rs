@@ -0,0 +1,122 @@
+use std::{
+	collections::{BTreeMap, HashMap},
+	path::Path,
+	str::FromStr,
+};
+
+use super::{Currency, Date, ForeignMoney, Money, UnitsError};
+
+/// Date-indexed table of euro reference rates, quoted as units of the foreign
+/// currency per euro (the convention used by the ECB and Banco de Portugal).
+///
+/// The table is read from a local text file with one rate per line:
+///
+/// ```text
+/// # date, currency, units per euro
+/// 2022-01-03, USD, 1.1355
+/// 2022-01-03, GBP, 0.83960
+/// ```
+///
+/// Blank lines and lines starting with `#` are ignored.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ExchangeRates {
+	rates: HashMap<Currency, BTreeMap<Date, f64>>,
+}
+
+impl ExchangeRates {
+	pub fn from_file<P: AsRef<Path>>(path: P) -> Result<Self, UnitsError> {
+		std::fs::read_to_string(path)?.parse()
+	}
+
+	pub fn insert(&mut self, date: Date, currency: Currency, units_per_euro: f64) -> Result<(), UnitsError> {
+		if !(units_per_euro.is_finite() && units_per_euro > 0.0) {
+			return Err(UnitsError::InvalidExchangeRate(format!("{} {} {}", date, currency, units_per_euro)));
+		}
+		self.rates.entry(currency).or_default().insert(date, units_per_euro);
+		Ok(())
+	}
+
+	/// Rate in effect on `date`: the one published that day or, for weekends
+	/// and holidays, the last one published before it.
+	pub fn rate(&self, currency: Currency, date: Date) -> Result<f64, UnitsError> {
+		if currency == Currency::EUR {
+			return Ok(1.0);
+		}
+		(self.rates.get(&currency))
+			.and_then(|rates| rates.range(..=date).next_back())
+			.map(|(_, rate)| *rate)
+			.ok_or(UnitsError::MissingExchangeRate(currency, date))
+	}
+
+	/// Converts an amount received on `date` (the payment date) into euros.
+	pub fn to_eur(&self, money: &ForeignMoney, date: Date) -> Result<Money, UnitsError> {
+		Ok(money.amount() / self.rate(money.currency(), date)?)
+	}
+}
+
+impl FromStr for ExchangeRates {
+	type Err = UnitsError;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let mut table = Self::default();
+		for line in s.lines().map(str::trim).filter(|l| !l.is_empty() && !l.starts_with('#')) {
+			let invalid = || UnitsError::InvalidExchangeRate(line.to_string());
+			let fields: Vec<_> = line.split(',').map(str::trim).collect();
+			let [date, currency, rate] = fields[..] else {
+				return Err(invalid());
+			};
+			table.insert(date.parse()?, currency.parse()?, rate.parse().map_err(|_| invalid())?)?;
+		}
+		Ok(table)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const TABLE: &str = "
+		# date, currency, units per euro
+		2022-01-03, USD, 1.1355
+		2022-01-03, GBP, 0.8396
+		2022-01-07, USD, 1.1298
+	";
+
+	fn date(s: &str) -> Date { s.parse().unwrap() }
+
+	#[test]
+	fn converts_at_rate_of_payment_date() {
+		let rates: ExchangeRates = TABLE.parse().unwrap();
+		let paid = ForeignMoney::new(1135.5, Currency::USD);
+		assert_eq!(rates.to_eur(&paid, date("2022-01-03")).unwrap(), Money::new(1000.0));
+		// No rate published on the 5th, so the one from the 3rd is used
+		assert_eq!(rates.to_eur(&paid, date("2022-01-05")).unwrap(), Money::new(1000.0));
+		assert_eq!(
+			rates.to_eur(&ForeignMoney::new(1129.8, Currency::USD), date("2022-01-07")).unwrap(),
+			Money::new(1000.0)
+		);
+		assert_eq!(
+			rates.to_eur(&ForeignMoney::new(10.0, Currency::EUR), date("2000-01-01")).unwrap(),
+			Money::new(10.0)
+		);
+	}
+
+	#[test]
+	fn missing_rates_are_errors() {
+		let rates: ExchangeRates = TABLE.parse().unwrap();
+		assert!(rates.to_eur(&ForeignMoney::new(1.0, Currency::USD), date("2021-12-31")).is_err());
+		assert!(rates.to_eur(&ForeignMoney::new(1.0, Currency::CHF), date("2022-01-03")).is_err());
+		assert!("2022-01-03, USD".parse::<ExchangeRates>().is_err());
+		assert!("2022-01-03, USD, -1.0".parse::<ExchangeRates>().is_err());
+	}
+
+	#[test]
+	fn currencies_must_match() {
+		let usd = ForeignMoney::new(10.0, Currency::USD);
+		let gbp = ForeignMoney::new(10.0, Currency::GBP);
+		assert_eq!(usd.add(&usd).unwrap(), ForeignMoney::new(20.0, Currency::USD));
+		assert!(usd.add(&gbp).is_err());
+		assert!(usd.sub(&gbp).is_err());
+		assert_eq!(usd.partial_cmp(&gbp), None);
+	}
+}
@@ -0,0 +1,193 @@
+use std::fmt::Display;
+
+use super::{Money, MoneyRate, UnitsError};
+
+/// Conventions for writing amounts of money.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Locale {
+	/// `1234.56€`, as printed by `Display`. Commas are accepted as thousands
+	/// separators when parsing.
+	#[default]
+	Plain,
+	/// `1.234,56 €`, as in Portuguese payslips and AT documents.
+	PtPt,
+}
+
+impl Locale {
+	fn separators(&self) -> (char, char) {
+		match self {
+			Self::Plain => (',', '.'),
+			Self::PtPt => ('.', ','),
+		}
+	}
+
+	/// Guesses the locale of a number from its separators: a comma after any
+	/// dots is a pt-PT decimal comma. Ambiguous inputs such as `1.234` are
+	/// read as plain decimals, so documents known to be pt-PT should be parsed
+	/// with [`Money::parse`].
+	fn detect(s: &str) -> Self {
+		match (s.rfind(','), s.rfind('.')) {
+			(Some(comma), Some(dot)) if comma < dot => Self::Plain,
+			(Some(_), _) => Self::PtPt,
+			_ => Self::Plain,
+		}
+	}
+}
+
+const SPACES: [char; 3] = [' ', '\u{a0}', '\u{202f}'];
+const DECIMALS: usize = 4;
+
+impl Money {
+	/// Parses an amount written with the conventions of `locale`. The euro
+	/// sign (or `EUR`) may come before or after the number, and spaces may be
+	/// used to group thousands.
+	pub fn parse(s: &str, locale: Locale) -> Result<Self, UnitsError> {
+		let invalid = || UnitsError::InvalidMoney(s.to_string());
+		let text = s.trim_matches(&SPACES[..]);
+		let text = (text.strip_prefix('€').or_else(|| text.strip_prefix("EUR")))
+			.or_else(|| text.strip_suffix('€').or_else(|| text.strip_suffix("EUR")))
+			.unwrap_or(text)
+			.trim_matches(&SPACES[..]);
+		let (negative, text) = match text.strip_prefix('-').or_else(|| text.strip_prefix('−')) {
+			Some(rest) => (true, rest),
+			None => (false, text),
+		};
+		let digits: String = text.chars().filter(|c| !SPACES.contains(c)).collect();
+
+		let (thousands, decimal) = locale.separators();
+		let (integer, fraction) = match digits.split_once(decimal) {
+			Some((integer, fraction)) => (integer, fraction),
+			None => (digits.as_str(), ""),
+		};
+		let groups: Vec<_> = integer.split(thousands).collect();
+		let grouped = groups.len() == 1 || groups[1..].iter().all(|g| g.len() == 3) && groups[0].len() <= 3;
+		let all_digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+		if integer.is_empty() || !grouped || !groups.iter().all(|g| !g.is_empty() && all_digits(g)) {
+			return Err(invalid());
+		}
+		if !all_digits(fraction) || digits.ends_with(decimal) {
+			return Err(invalid());
+		}
+
+		let euros: i64 = groups.concat().parse().map_err(|_| invalid())?;
+		let padded = format!("{:0<width$}", fraction, width = DECIMALS + 1);
+		let (kept, next) = padded.split_at(DECIMALS);
+		let round_up = next.starts_with(|c: char| c >= '5');
+		let units = (euros.checked_mul(10i64.pow(DECIMALS as u32)))
+			.and_then(|units| units.checked_add(kept[..DECIMALS].parse::<i64>().ok()? + round_up as i64))
+			.filter(|&units| units <= Money::MAX.units())
+			.ok_or_else(invalid)?;
+		Ok(Money::from_units(if negative { -units } else { units }))
+	}
+
+	/// Displays the amount with the conventions of `locale`, *e.g.*
+	/// `format!("{:.2}", money.localized(Locale::PtPt))`.
+	pub fn localized(&self, locale: Locale) -> Localized<Money> { Localized { value: *self, locale } }
+}
+
+impl std::str::FromStr for Money {
+	type Err = UnitsError;
+
+	/// Parses both plain (`1234.56`) and pt-PT (`1.234,56 €`) amounts.
+	fn from_str(s: &str) -> Result<Self, Self::Err> { Money::parse(s, Locale::detect(s)) }
+}
+
+impl<P> MoneyRate<P> {
+	pub fn localized(&self, locale: Locale) -> Localized<&Self> { Localized { value: self, locale } }
+}
+
+/// An amount displayed with the conventions of some [`Locale`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Localized<T> {
+	value: T,
+	locale: Locale,
+}
+
+impl Display for Localized<Money> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		let precision = f.precision().unwrap_or(2);
+		// Round in integer arithmetic so that, e.g., 0.125€ shows as 0.13€.
+		let shown = precision.min(DECIMALS) as u32;
+		let step = 10i64.pow(DECIMALS as u32 - shown);
+		let units = self.value.units() / step + (2 * (self.value.units() % step) / step);
+		let scale = 10i64.pow(shown);
+
+		let (thousands, decimal) = self.locale.separators();
+		let integer = (units.abs() / scale).to_string();
+		let mut number = String::from(if units < 0 { "-" } else { "" });
+		for (i, digit) in integer.chars().enumerate() {
+			if i > 0 && (integer.len() - i).is_multiple_of(3) && self.locale == Locale::PtPt {
+				number.push(thousands);
+			}
+			number.push(digit);
+		}
+		if precision > 0 {
+			let fraction = format!("{:0width$}", units.abs() % scale, width = shown as usize);
+			number = format!("{}{}{:0<precision$}", number, decimal, fraction);
+		}
+
+		let euro = match self.locale {
+			Locale::Plain => "€",
+			Locale::PtPt => " €",
+		};
+		match f.width() {
+			Some(width) => {
+				write!(f, "{:>width$}{}", number, euro, width = width.saturating_sub(euro.chars().count()))
+			}
+			None => write!(f, "{}{}", number, euro),
+		}
+	}
+}
+
+impl<P: Display> Display for Localized<&MoneyRate<P>> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.value.quantity_ref().localized(self.locale).fmt(f)?;
+		self.value.period_ref().fmt(f)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::units::{Monthly, Yearly};
+
+	#[test]
+	fn parse_pt_pt() {
+		assert_eq!(Money::parse("1.234,56 €", Locale::PtPt).unwrap(), Money::new(1234.56));
+		assert_eq!(Money::parse("1 234,56€", Locale::PtPt).unwrap(), Money::new(1234.56));
+		assert_eq!(Money::parse("-12,5", Locale::PtPt).unwrap(), Money::new(-12.5));
+		assert_eq!(Money::parse("1.234", Locale::PtPt).unwrap(), Money::new(1234.0));
+		assert_eq!(Money::parse("EUR 1.000.000,00", Locale::PtPt).unwrap(), Money::new(1_000_000.0));
+		assert!(Money::parse("10.000.000.000.000 €", Locale::PtPt).is_err());
+		assert!(Money::parse("1.23,45", Locale::PtPt).is_err());
+		assert!(Money::parse("12,", Locale::PtPt).is_err());
+		assert!(Money::parse("€", Locale::PtPt).is_err());
+	}
+
+	#[test]
+	fn parse_detects_locale() {
+		assert_eq!("1234.56".parse::<Money>().unwrap(), Money::new(1234.56));
+		assert_eq!("1234.56€".parse::<Money>().unwrap(), Money::new(1234.56));
+		assert_eq!("1,234.56".parse::<Money>().unwrap(), Money::new(1234.56));
+		assert_eq!("1.234,56 €".parse::<Money>().unwrap(), Money::new(1234.56));
+		assert_eq!("4,77".parse::<Money>().unwrap(), Money::new(4.77));
+		assert_eq!("0.123456".parse::<Money>().unwrap(), Money::new(0.1235));
+		assert!("abc".parse::<Money>().is_err());
+	}
+
+	#[test]
+	fn format_pt_pt() {
+		assert_eq!(Money::new(1234.56).localized(Locale::PtPt).to_string(), "1.234,56 €");
+		assert_eq!(Money::new(-1234567.891).localized(Locale::PtPt).to_string(), "-1.234.567,89 €");
+		assert_eq!(format!("{:.0}", Money::new(999.5).localized(Locale::PtPt)), "1.000 €");
+		assert_eq!(format!("{:12}", Money::new(705.0).localized(Locale::PtPt)), "    705,00 €");
+		assert_eq!(Money::new(1234.56).localized(Locale::Plain).to_string(), Money::new(1234.56).to_string());
+	}
+
+	#[test]
+	fn format_rates() {
+		assert_eq!(MoneyRate::new(1270.0, Monthly::M14).to_string(), "1270.00€/mês ×14");
+		assert_eq!(MoneyRate::new(1270.0, Yearly).localized(Locale::PtPt).to_string(), "1.270,00 €/ano");
+		assert_eq!(format!("{:.0}", MoneyRate::new(4.77, Monthly::M11)), "5€/mês ×11");
+	}
+}
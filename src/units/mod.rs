@@ -1,10 +1,39 @@
+/// Implements serde's traits through `Display` and `FromStr`, for types whose
+/// textual form is already stable and readable.
+#[cfg(feature = "serde")]
+macro_rules! serde_via_str {
+	($type:ty) => {
+		impl serde::Serialize for $type {
+			fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.collect_str(self)
+			}
+		}
+		impl<'de> serde::Deserialize<'de> for $type {
+			fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+			}
+		}
+	};
+}
+
+mod currency;
+mod date;
+mod exchange_rates;
+mod locale;
 mod money;
 mod quantity_per_time;
 mod yearly_plan;
 
-pub use money::Money;
-pub use quantity_per_time::{Hourly, Monthly, QuantityPerTime, Workdaily, Yearly};
-pub use yearly_plan::YearlyPlan;
+pub use currency::{Currency, ForeignMoney};
+pub use date::{Date, Weekday};
+pub use exchange_rates::ExchangeRates;
+pub use locale::{Locale, Localized};
+pub use money::{Money, Rounding};
+pub use quantity_per_time::{
+	Daily, FixedPeriod, Hourly, Monthly, Period, QuantityPerTime, Quarterly, Weekly, WorkSchedule, Workdaily,
+	Yearly,
+};
+pub use yearly_plan::{PaymentCounts, YearlyPlan};
 
 pub type TaxRate = f64;
 pub type MoneyRate<P> = QuantityPerTime<Money, P>;
@@ -15,4 +44,18 @@ use thiserror::Error;
 pub enum UnitsError {
 	#[error("quantities with different periods can't be used in binary ops")]
 	PeriodIsDifferent,
+	#[error("amounts in {0} and {1} can't be used in binary ops without converting first")]
+	CurrencyIsDifferent(Currency, Currency),
+	#[error("invalid currency code: {0}")]
+	InvalidCurrency(String),
+	#[error("invalid date: {0}")]
+	InvalidDate(String),
+	#[error("invalid amount of money: {0}")]
+	InvalidMoney(String),
+	#[error("invalid exchange rate: {0}")]
+	InvalidExchangeRate(String),
+	#[error("no {0} exchange rate on or before {1}")]
+	MissingExchangeRate(Currency, Date),
+	#[error(transparent)]
+	Io(#[from] std::io::Error),
 }
@@ -1,67 +1,219 @@
 use std::{
 	fmt::Display,
-	ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign},
+	ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
 };
 
-#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
-pub struct Money(f64);
+use super::{Locale, UnitsError};
+
+/// Number of internal units in one euro. Amounts are kept as an exact integer
+/// number of ten-thousandths of a euro, so sums never drift, and rates
+/// multiplied into them are rounded to that resolution. Rounding to the cent
+/// only happens where payslips and the tax authority round, through
+/// [`Money::round`].
+const UNITS_PER_EURO: i64 = 10_000;
+const UNITS_PER_CENT: i64 = UNITS_PER_EURO / 100;
+/// Largest amount, in euros either way, that [`Money::new`] and
+/// [`Money::parse`] accept. It keeps sums of many amounts far from the range
+/// of the internal integer; arithmetic past it saturates instead of wrapping.
+const MAX_EUROS: f64 = 1e12;
+
+/// How an amount is brought to whole cents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rounding {
+	/// Half a cent or more goes up (away from zero), as in payslips and SS
+	/// contributions.
+	HalfUp,
+	/// Fractions of a cent are dropped (towards zero), as AT requires for the
+	/// amounts to withhold.
+	Truncate,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Money(i64);
+
+/// Serialized as a plain number of euros, *e.g.* `1234.56`.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Money {
+	fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.serialize_f64(self.value())
+	}
+}
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Money {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		Money::try_new(f64::deserialize(deserializer)?).map_err(serde::de::Error::custom)
+	}
+}
+
 impl Money {
-	pub const fn new(value: f64) -> Self { Self(value) }
+	/// Largest amount that can be built from euros or parsed.
+	pub const MAX: Money = Money::new(MAX_EUROS);
+	pub const MIN: Money = Money::new(-MAX_EUROS);
+
+	/// # Panics
+	///
+	/// If `value` is not finite or is beyond [`Money::MAX`] either way; use
+	/// [`Money::try_new`] for amounts that come from outside.
+	pub const fn new(value: f64) -> Self {
+		match Self::checked(value) {
+			Some(money) => money,
+			None => panic!("amount of money out of range"),
+		}
+	}
+
+	pub fn try_new(value: f64) -> Result<Self, UnitsError> {
+		Self::checked(value).ok_or_else(|| UnitsError::InvalidMoney(value.to_string()))
+	}
+
+	const fn checked(value: f64) -> Option<Self> {
+		match value.is_finite() && value.abs() <= MAX_EUROS {
+			true => Some(Self(round_half_up(value * UNITS_PER_EURO as f64))),
+			false => None,
+		}
+	}
+
+	pub const fn from_cents(cents: i64) -> Self { Self(cents.saturating_mul(UNITS_PER_CENT)) }
+
+	pub const fn zero() -> Self { Self(0) }
+
+	pub(super) const fn from_units(units: i64) -> Self { Self(units) }
+
+	pub(super) const fn units(&self) -> i64 { self.0 }
+
+	pub fn value(&self) -> f64 { self.0 as f64 / UNITS_PER_EURO as f64 }
+
+	/// Whole cents, with any fraction of a cent dropped.
+	pub const fn cents(&self) -> i64 { self.0 / UNITS_PER_CENT }
+
+	pub fn abs(&self) -> Self { Money(self.0.saturating_abs()) }
 
-	pub const fn value(&self) -> f64 { self.0 }
+	pub fn min(self, other: Self) -> Self { Money(self.0.min(other.0)) }
 
-	pub fn abs(&self) -> Self { Money(self.0.abs()) }
+	pub fn max(self, other: Self) -> Self { Money(self.0.max(other.0)) }
+
+	/// Rounds to whole cents with the given rule.
+	pub fn round(self, rounding: Rounding) -> Self {
+		let remainder = self.0 % UNITS_PER_CENT;
+		let truncated = self.0 - remainder;
+		match rounding {
+			Rounding::Truncate => Money(truncated),
+			Rounding::HalfUp if remainder.abs() * 2 >= UNITS_PER_CENT => {
+				Money(truncated + remainder.signum() * UNITS_PER_CENT)
+			}
+			Rounding::HalfUp => Money(truncated),
+		}
+	}
+}
+
+const fn round_half_up(value: f64) -> i64 {
+	if value < 0.0 {
+		(value - 0.5) as i64
+	} else {
+		(value + 0.5) as i64
+	}
 }
 
 impl From<f64> for Money {
-	fn from(money: f64) -> Self { Money(money) }
+	fn from(money: f64) -> Self { Money::new(money) }
 }
 
 impl Display for Money {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-		let precision = if let Some(p) = f.precision() { p } else { 2 };
-		if let Some(width) = f.width() {
-			write!(f, "{:width$.precision$}€", self.0, width = width - 1 /* for € */)
-		} else {
-			write!(f, "{:.precision$}€", self.0)
-		}
+		self.localized(Locale::Plain).fmt(f)
 	}
 }
 
+impl Neg for Money {
+	type Output = Self;
+
+	fn neg(self) -> Self::Output { Money(self.0.saturating_neg()) }
+}
+
 impl Mul<f64> for Money {
 	type Output = Self;
 
-	fn mul(self, rhs: f64) -> Self::Output { Money(self.0.mul(rhs)) }
+	fn mul(self, rhs: f64) -> Self::Output { Money(round_half_up(self.0 as f64 * rhs)) }
 }
 impl Mul<Money> for f64 {
 	type Output = Money;
 
-	fn mul(self, rhs: Self::Output) -> Self::Output { Money(self.mul(rhs.0)) }
+	fn mul(self, rhs: Self::Output) -> Self::Output { rhs.mul(self) }
 }
 impl MulAssign<f64> for Money {
-	fn mul_assign(&mut self, rhs: f64) { self.0.mul_assign(rhs) }
+	fn mul_assign(&mut self, rhs: f64) { *self = self.mul(rhs) }
 }
 impl Div<f64> for Money {
 	type Output = Self;
 
-	fn div(self, rhs: f64) -> Self::Output { Money(self.0.div(rhs)) }
+	fn div(self, rhs: f64) -> Self::Output { Money(round_half_up(self.0 as f64 / rhs)) }
 }
 impl DivAssign<f64> for Money {
-	fn div_assign(&mut self, rhs: f64) { self.0.div_assign(rhs) }
+	fn div_assign(&mut self, rhs: f64) { *self = self.div(rhs) }
 }
 impl Add<Self> for Money {
 	type Output = Self;
 
-	fn add(self, rhs: Self) -> Self::Output { Money(self.0.add(rhs.0)) }
+	fn add(self, rhs: Self) -> Self::Output { Money(self.0.saturating_add(rhs.0)) }
 }
 impl AddAssign<Self> for Money {
-	fn add_assign(&mut self, rhs: Self) { self.0.add_assign(rhs.0) }
+	fn add_assign(&mut self, rhs: Self) { *self = self.add(rhs) }
 }
 impl Sub<Self> for Money {
 	type Output = Self;
 
-	fn sub(self, rhs: Self) -> Self::Output { Money(self.0.sub(rhs.0)) }
+	fn sub(self, rhs: Self) -> Self::Output { Money(self.0.saturating_sub(rhs.0)) }
 }
 impl SubAssign<Self> for Money {
-	fn sub_assign(&mut self, rhs: Self) { self.0.sub_assign(rhs.0) }
+	fn sub_assign(&mut self, rhs: Self) { *self = self.sub(rhs) }
+}
+impl std::iter::Sum for Money {
+	fn sum<I: Iterator<Item = Self>>(iter: I) -> Self { iter.fold(Money::zero(), Add::add) }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn sums_do_not_drift() {
+		let total: Money = std::iter::repeat_n(Money::new(0.1), 1000).sum();
+		assert_eq!(total, Money::new(100.0));
+		assert_eq!(Money::new(0.1) + Money::new(0.2), Money::new(0.3));
+	}
+
+	#[test]
+	fn round_half_up() {
+		assert_eq!(Money::new(1.005).round(Rounding::HalfUp), Money::from_cents(101));
+		assert_eq!(Money::new(1.0049).round(Rounding::HalfUp), Money::from_cents(100));
+		assert_eq!(Money::new(-1.005).round(Rounding::HalfUp), Money::from_cents(-101));
+		assert_eq!((Money::new(1270.0) * 0.11).round(Rounding::HalfUp), Money::new(139.70));
+	}
+
+	#[test]
+	fn truncate() {
+		assert_eq!(Money::new(1.0099).round(Rounding::Truncate), Money::from_cents(100));
+		assert_eq!(Money::new(-1.0099).round(Rounding::Truncate), Money::from_cents(-100));
+		assert_eq!((Money::new(2364.0) * 0.251).round(Rounding::Truncate), Money::new(593.36));
+	}
+
+	#[test]
+	fn out_of_range() {
+		assert!(Money::try_new(1e16).is_err());
+		assert!(Money::try_new(f64::INFINITY).is_err());
+		assert!(Money::try_new(f64::NAN).is_err());
+		assert_eq!(Money::try_new(-1e12).unwrap(), Money::MIN);
+		assert!(std::panic::catch_unwind(|| Money::new(1e16)).is_err());
+		let huge = Money::from_units(i64::MAX);
+		assert_eq!(huge + huge, huge);
+		assert_eq!(-huge - huge, Money::from_units(i64::MIN));
+		assert_eq!(huge * 2.0, huge);
+	}
+
+	#[test]
+	fn display() {
+		assert_eq!(Money::new(1234.565).to_string(), "1234.57€");
+		assert_eq!(format!("{:.0}", Money::new(-2.5)), "-3€");
+		assert_eq!(format!("{:8.1}", Money::new(12.34)), "   12.3€");
+		assert_eq!(format!("{:.5}", Money::new(0.1234)), "0.12340€");
+	}
 }
@@ -1,9 +1,37 @@
-use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::{
+	fmt::Display,
+	ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign},
+};
 
 use super::{Money, MoneyRate, UnitsError};
 
+/// Periods without parameters are serialized by name, *e.g.* `"Yearly"`.
+#[cfg(feature = "serde")]
+macro_rules! serde_unit_period {
+	($period:ident) => {
+		impl serde::Serialize for $period {
+			fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+				serializer.serialize_str(stringify!($period))
+			}
+		}
+		impl<'de> serde::Deserialize<'de> for $period {
+			fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+				match String::deserialize(deserializer)?.as_str() {
+					stringify!($period) => Ok($period),
+					other => Err(serde::de::Error::invalid_value(
+						serde::de::Unexpected::Str(other),
+						&stringify!($period),
+					)),
+				}
+			}
+		}
+	};
+}
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct QuantityPerTime<T, P> {
+	#[cfg_attr(feature = "serde", serde(rename = "quantity"))]
 	pub qty: T,
 	period: P,
 }
@@ -15,6 +43,10 @@ impl<T, P> QuantityPerTime<T, P> {
 	pub const fn quantity_ref(&self) -> &T { &self.qty }
 
 	pub const fn period_ref(&self) -> &P { &self.period }
+
+	pub fn map<U, F: FnOnce(T) -> U>(self, f: F) -> QuantityPerTime<U, P> {
+		QuantityPerTime { qty: f(self.qty), period: self.period }
+	}
 }
 
 impl<T: Copy + Sized, P> QuantityPerTime<T, P> {
@@ -25,25 +57,80 @@ impl<T, P: Copy + Sized> QuantityPerTime<T, P> {
 	pub fn period(&self) -> P { self.period }
 }
 
-impl<T: Clone + Add<T, Output = T>, P1, P2> Add<QuantityPerTime<T, P2>> for QuantityPerTime<T, P1>
-where
-	QuantityPerTime<T, Yearly>: From<QuantityPerTime<T, P2>>,
-	QuantityPerTime<T, Yearly>: From<QuantityPerTime<T, P1>>,
-{
-	type Output = QuantityPerTime<T, Yearly>;
+/// Writes the quantity, with the formatting options given, followed by the
+/// period, *e.g.* `1270.00€/mês ×14`.
+impl<T: Display, P: Display> Display for QuantityPerTime<T, P> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		self.qty.fmt(f)?;
+		self.period.fmt(f)
+	}
+}
 
-	fn add(self, rhs: QuantityPerTime<T, P2>) -> Self::Output {
-		QuantityPerTime {
-			qty: QuantityPerTime::<T, Yearly>::from(self)
-				.qty
-				.add(QuantityPerTime::<T, Yearly>::from(rhs).qty),
-			period: Yearly,
-		}
+/// Working time that rates over some periods depend on. It has to be given
+/// explicitly to convert to or from [`Workdaily`] and [`Hourly`] rates.
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WorkSchedule {
+	pub hours_per_week: f64,
+	pub workdays_in_year: u32,
+}
+impl WorkSchedule {
+	pub fn new(hours_per_week: f64, workdays_in_year: u32) -> Self {
+		Self { hours_per_week, workdays_in_year }
 	}
 }
+impl Default for WorkSchedule {
+	/// 40 hours per week and the customary 22 workdays over 11 months.
+	fn default() -> Self { Self { hours_per_week: 40.0, workdays_in_year: 11 * 22 } }
+}
+
+/// A period over which a quantity is paid or accrued.
+pub trait Period: Copy + PartialEq + std::fmt::Debug {
+	/// How many of these periods make up a year.
+	fn per_year(&self, schedule: &WorkSchedule) -> f64;
+}
+
+impl<T: Mul<f64, Output = T>, P: Period> QuantityPerTime<T, P> {
+	/// Converts into the equivalent rate over another period, keeping the
+	/// yearly total. Monthly rates count one period per payment, so an
+	/// [`Monthly::M14`] rate is spread over 14 payments; for the Labour Code
+	/// hourly wage see [`QuantityPerTime::hourly_wage`].
+	pub fn convert<Q: Period>(self, to: Q, schedule: &WorkSchedule) -> QuantityPerTime<T, Q> {
+		let factor = self.period.per_year(schedule) / to.per_year(schedule);
+		QuantityPerTime { qty: self.qty * factor, period: to }
+	}
+}
+
+/// A period with a single value, so that any two rates over it add up.
+/// [`Monthly`] isn't one, as rates over [`Monthly::M11`] and [`Monthly::M14`]
+/// can't be added without converting them first.
+pub trait FixedPeriod: Period {}
+
+/// Adding rates over the same period keeps that period. Rates over different
+/// periods, including monthly rates, must be checked or converted first.
+impl<T: Add<T, Output = T>, P: FixedPeriod> Add<Self> for QuantityPerTime<T, P> {
+	type Output = Self;
+
+	fn add(self, rhs: Self) -> Self::Output {
+		QuantityPerTime { qty: self.qty.add(rhs.qty), period: self.period }
+	}
+}
+impl<T: AddAssign<T>, P: FixedPeriod> AddAssign<Self> for QuantityPerTime<T, P> {
+	fn add_assign(&mut self, rhs: Self) { self.qty.add_assign(rhs.qty) }
+}
+impl<T: Sub<T, Output = T>, P: FixedPeriod> Sub<Self> for QuantityPerTime<T, P> {
+	type Output = Self;
+
+	fn sub(self, rhs: Self) -> Self::Output {
+		QuantityPerTime { qty: self.qty.sub(rhs.qty), period: self.period }
+	}
+}
+impl<T: SubAssign<T>, P: FixedPeriod> SubAssign<Self> for QuantityPerTime<T, P> {
+	fn sub_assign(&mut self, rhs: Self) { self.qty.sub_assign(rhs.qty) }
+}
 
 impl<T: Clone + Add<T, Output = T>, P: Clone + Eq> QuantityPerTime<T, P> {
-	pub fn add(&self, rhs: &Self) -> Result<Self, UnitsError> {
+	pub fn checked_add(&self, rhs: &Self) -> Result<Self, UnitsError> {
 		if self.period == rhs.period {
 			Ok(Self { qty: self.qty.clone() + rhs.qty.clone(), period: self.period.clone() })
 		} else {
@@ -51,6 +138,15 @@ impl<T: Clone + Add<T, Output = T>, P: Clone + Eq> QuantityPerTime<T, P> {
 		}
 	}
 }
+impl<T: Clone + Sub<T, Output = T>, P: Clone + Eq> QuantityPerTime<T, P> {
+	pub fn checked_sub(&self, rhs: &Self) -> Result<Self, UnitsError> {
+		if self.period == rhs.period {
+			Ok(Self { qty: self.qty.clone() - rhs.qty.clone(), period: self.period.clone() })
+		} else {
+			Err(UnitsError::PeriodIsDifferent)
+		}
+	}
+}
 impl<T: PartialOrd, P: Eq> PartialOrd for QuantityPerTime<T, P> {
 	fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
 		if self.period == other.period {
@@ -89,41 +185,50 @@ impl<U, T: DivAssign<U>, P> DivAssign<U> for QuantityPerTime<T, P> {
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Yearly;
+#[cfg(feature = "serde")]
+serde_unit_period!(Yearly);
+impl Display for Yearly {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "/ano") }
+}
+impl Period for Yearly {
+	fn per_year(&self, _schedule: &WorkSchedule) -> f64 { 1.0 }
+}
+impl FixedPeriod for Yearly {}
 impl MoneyRate<Yearly> {
 	pub const fn zero() -> Self { Self { qty: Money::new(0.0), period: Yearly } }
 }
 impl Default for MoneyRate<Yearly> {
 	fn default() -> Self { Self::zero() }
 }
-impl<T: AddAssign<T>> AddAssign<Self> for QuantityPerTime<T, Yearly> {
-	fn add_assign(&mut self, rhs: Self) { self.qty.add_assign(rhs.qty) }
-}
-impl<T: Sub<T, Output = T>> Sub<Self> for QuantityPerTime<T, Yearly> {
-	type Output = Self;
 
-	fn sub(self, rhs: Self) -> Self::Output {
-		QuantityPerTime { qty: self.qty.sub(rhs.qty), period: self.period }
-	}
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Quarterly;
+#[cfg(feature = "serde")]
+serde_unit_period!(Quarterly);
+impl Display for Quarterly {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "/trimestre") }
 }
-impl<T: SubAssign<T>> SubAssign<Self> for QuantityPerTime<T, Yearly> {
-	fn sub_assign(&mut self, rhs: Self) { self.qty.sub_assign(rhs.qty) }
+impl Period for Quarterly {
+	fn per_year(&self, _schedule: &WorkSchedule) -> f64 { 4.0 }
 }
-
-pub struct Quarterly;
+impl FixedPeriod for Quarterly {}
 impl<T: Mul<f64, Output = T>> From<QuantityPerTime<T, Quarterly>> for QuantityPerTime<T, Yearly> {
-	fn from(monthly: QuantityPerTime<T, Quarterly>) -> Self {
-		Self { qty: monthly.qty * 4.0, period: Yearly }
+	fn from(quarterly: QuantityPerTime<T, Quarterly>) -> Self {
+		Self { qty: quarterly.qty * 4.0, period: Yearly }
 	}
 }
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Monthly {
 	M11,
 	M12,
 	M14,
 }
 impl Monthly {
-	fn months_in_year(&self) -> f64 {
+	/// Number of payments in a year: 11 skips the vacation month, 14 adds the
+	/// vacation and Christmas subsidies.
+	pub fn months_in_year(&self) -> f64 {
 		match self {
 			Self::M11 => 11.0,
 			Self::M12 => 12.0,
@@ -131,6 +236,14 @@ impl Monthly {
 		}
 	}
 }
+impl Display for Monthly {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "/mês ×{}", self.months_in_year())
+	}
+}
+impl Period for Monthly {
+	fn per_year(&self, _schedule: &WorkSchedule) -> f64 { self.months_in_year() }
+}
 impl<T: Mul<f64, Output = T>> From<QuantityPerTime<T, Monthly>> for QuantityPerTime<T, Yearly> {
 	fn from(monthly: QuantityPerTime<T, Monthly>) -> Self {
 		Self { qty: monthly.qty * monthly.period.months_in_year(), period: Yearly }
@@ -147,35 +260,126 @@ impl<T: Clone + Mul<f64, Output = T>> From<&QuantityPerTime<T, Monthly>> for Qua
 impl<T: Clone + Mul<f64, Output = T>> QuantityPerTime<T, Monthly> {
 	pub fn as_yearly(&self) -> QuantityPerTime<T, Yearly> { QuantityPerTime::<T, Yearly>::from(self) }
 }
+impl<T: Mul<f64, Output = T>> QuantityPerTime<T, Monthly> {
+	/// Hourly wage as defined in the Labour Code (art. 271.º), (Rm × 12) / (52
+	/// × n), where n is the normal weekly working time. Subsidies are not part
+	/// of it, so it is the same whatever the number of payments in a year.
+	pub fn hourly_wage(self, schedule: &WorkSchedule) -> QuantityPerTime<T, Hourly> {
+		QuantityPerTime { qty: self.qty * (12.0 / (52.0 * schedule.hours_per_week)), period: Hourly }
+	}
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Weekly;
+#[cfg(feature = "serde")]
+serde_unit_period!(Weekly);
+impl Display for Weekly {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "/semana") }
+}
+impl Period for Weekly {
+	fn per_year(&self, _schedule: &WorkSchedule) -> f64 { 52.0 }
+}
+impl FixedPeriod for Weekly {}
+impl<T: Mul<f64, Output = T>> From<QuantityPerTime<T, Weekly>> for QuantityPerTime<T, Yearly> {
+	fn from(weekly: QuantityPerTime<T, Weekly>) -> Self { Self { qty: weekly.qty * 52.0, period: Yearly } }
+}
+
+/// Per calendar day, weekends and holidays included.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Daily;
+#[cfg(feature = "serde")]
+serde_unit_period!(Daily);
+impl Display for Daily {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "/dia") }
+}
+impl Period for Daily {
+	fn per_year(&self, _schedule: &WorkSchedule) -> f64 { 365.0 }
+}
+impl FixedPeriod for Daily {}
+impl<T: Mul<f64, Output = T>> From<QuantityPerTime<T, Daily>> for QuantityPerTime<T, Yearly> {
+	fn from(daily: QuantityPerTime<T, Daily>) -> Self { Self { qty: daily.qty * 365.0, period: Yearly } }
+}
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct Workdaily;
-impl Workdaily {
-	pub fn actual_workdays_in_year(year: u32, vacation_days: u32) -> u32 {
-		match year {
-			2022 => 251 - vacation_days, // https://www.dias-uteis.pt/dias-uteis_feriados_2022.htm
-			_ => unimplemented!(),
-		}
-	}
+#[cfg(feature = "serde")]
+serde_unit_period!(Workdaily);
+impl Display for Workdaily {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "/dia útil") }
 }
+impl Period for Workdaily {
+	fn per_year(&self, schedule: &WorkSchedule) -> f64 { schedule.workdays_in_year as f64 }
+}
+impl FixedPeriod for Workdaily {}
 
 impl<T: Mul<f64, Output = T>> QuantityPerTime<T, Workdaily> {
 	pub fn into_yearly(self, workdays_in_year: Option<u32>) -> QuantityPerTime<T, Yearly> {
-		let workdays_in_year = match workdays_in_year {
-			Some(days) => days as f64,
-			None => 11.0 * 22.0,
-		};
+		let workdays_in_year = workdays_in_year.map_or(11.0 * 22.0, |days| days as f64);
 		QuantityPerTime { qty: self.qty * workdays_in_year, period: Yearly }
 	}
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
-pub struct Hourly {
-	hours_per_week: f64,
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Hourly;
+#[cfg(feature = "serde")]
+serde_unit_period!(Hourly);
+impl Display for Hourly {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "/hora") }
+}
+impl Period for Hourly {
+	fn per_year(&self, schedule: &WorkSchedule) -> f64 { 52.0 * schedule.hours_per_week }
 }
+impl FixedPeriod for Hourly {}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn schedule() -> WorkSchedule { WorkSchedule::new(40.0, 242) }
+
+	#[test]
+	fn same_period_addition_keeps_period() {
+		let sum = MoneyRate::new(1000.0, Yearly) + MoneyRate::new(270.0, Yearly);
+		assert_eq!(sum, MoneyRate::new(1270.0, Yearly));
+		let sum = MoneyRate::new(1000.0, Monthly::M14).checked_add(&MoneyRate::new(270.0, Monthly::M14));
+		assert_eq!(sum.unwrap(), MoneyRate::new(1270.0, Monthly::M14));
+		let mut diff = MoneyRate::new(10.0, Weekly);
+		diff -= MoneyRate::new(4.0, Weekly);
+		assert_eq!(diff, MoneyRate::new(6.0, Weekly));
+	}
+
+	#[test]
+	fn different_periods_need_checking_or_converting() {
+		let useful = MoneyRate::new(100.0, Monthly::M11);
+		let regular = MoneyRate::new(1000.0, Monthly::M14);
+		assert!(useful.checked_add(&regular).is_err());
+		assert!(useful.checked_sub(&regular).is_err());
+		assert_eq!(useful.as_yearly() + regular.as_yearly(), MoneyRate::new(15100.0, Yearly));
+	}
+
+	#[test]
+	fn legal_hourly_wage() {
+		let monthly = MoneyRate::new(1040.0, Monthly::M14);
+		assert_eq!(monthly.hourly_wage(&schedule()), MoneyRate::new(6.0, Hourly));
+		assert_eq!(monthly.hourly_wage(&WorkSchedule::new(20.0, 242)), MoneyRate::new(12.0, Hourly));
+		let monthly = MoneyRate::new(1040.0, Monthly::M12);
+		assert_eq!(monthly.convert(Hourly, &schedule()), monthly.hourly_wage(&schedule()));
+	}
 
-impl<T: Mul<f64, Output = T>> From<QuantityPerTime<T, Hourly>> for QuantityPerTime<T, Yearly> {
-	fn from(hourly: QuantityPerTime<T, Hourly>) -> Self {
-		Self { qty: hourly.qty * hourly.period.hours_per_week * 52.0, period: Yearly }
+	#[test]
+	fn conversions_between_any_periods() {
+		let s = schedule();
+		let hourly = MoneyRate::new(10.0, Hourly);
+		assert_eq!(hourly.convert(Weekly, &s), MoneyRate::new(400.0, Weekly));
+		assert_eq!(hourly.convert(Yearly, &s), MoneyRate::new(20_800.0, Yearly));
+		assert_eq!(hourly.convert(Quarterly, &s), MoneyRate::new(5_200.0, Quarterly));
+		assert_eq!(MoneyRate::new(7.63, Workdaily).convert(Yearly, &s), MoneyRate::new(1846.46, Yearly));
+		assert_eq!(
+			MoneyRate::new(14_000.0, Yearly).convert(Monthly::M14, &s),
+			MoneyRate::new(1000.0, Monthly::M14)
+		);
+		assert_eq!(MoneyRate::new(730.0, Yearly).convert(Daily, &s), MoneyRate::new(2.0, Daily));
+		let quarterly = MoneyRate::new(3000.0, Quarterly).convert(Monthly::M12, &s);
+		assert_eq!(quarterly, MoneyRate::new(1000.0, Monthly::M12));
 	}
 }
@@ -2,16 +2,68 @@ use std::ops::{Add, AddAssign, Mul, Sub};
 
 use super::{Monthly, QuantityPerTime, Yearly};
 
+/// How many times each payment of a [`YearlyPlan`] is made in the year.
+/// Fractions stand for pro-rated payments, *e.g.* in the year of admission.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PaymentCounts {
+	pub regular: f64,
+	pub vacation: f64,
+	pub bonus: f64,
+}
+
+impl PaymentCounts {
+	/// Eleven regular months, the vacation month, and both subsidies.
+	pub const FULL_YEAR: Self = Self { regular: 11.0, vacation: 1.0, bonus: 2.0 };
+
+	/// Yearly total of a monthly payment, paid in the months its period covers.
+	pub fn yearly<T: Copy + Default + AddAssign<T> + Mul<f64, Output = T> + Add<T, Output = T>>(
+		&self, monthly: QuantityPerTime<T, Monthly>,
+	) -> QuantityPerTime<T, Yearly> {
+		let mut plan = YearlyPlan::default().with_payments(*self);
+		plan += monthly;
+		plan.yearly_total()
+	}
+}
+
+impl Default for PaymentCounts {
+	fn default() -> Self { Self::FULL_YEAR }
+}
+
+/// The amount of each kind of payment in the year: regular months, the
+/// vacation month (where headings paid 11 times a year are missing), and each
+/// subsidy.
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct YearlyPlan<T> {
 	pub regular: T,
 	pub vacation: T,
 	pub bonus: T,
+	pub payments: PaymentCounts,
 }
 
 impl<T> YearlyPlan<T> {
+	pub fn with_payments(mut self, payments: PaymentCounts) -> Self {
+		self.payments = payments;
+		self
+	}
+
 	pub fn map<U, F: FnMut(T) -> U>(self, mut f: F) -> YearlyPlan<U> {
-		YearlyPlan { regular: f(self.regular), vacation: f(self.vacation), bonus: f(self.bonus) }
+		YearlyPlan {
+			regular: f(self.regular),
+			vacation: f(self.vacation),
+			bonus: f(self.bonus),
+			payments: self.payments,
+		}
+	}
+
+	pub fn try_map<U, E, F: FnMut(T) -> Result<U, E>>(self, mut f: F) -> Result<YearlyPlan<U>, E> {
+		Ok(YearlyPlan {
+			regular: f(self.regular)?,
+			vacation: f(self.vacation)?,
+			bonus: f(self.bonus)?,
+			payments: self.payments,
+		})
 	}
 
 	pub fn combine<U, V, F: FnMut(T, U) -> V>(self, other: YearlyPlan<U>, mut f: F) -> YearlyPlan<V> {
@@ -19,13 +71,15 @@ impl<T> YearlyPlan<T> {
 			regular: f(self.regular, other.regular),
 			vacation: f(self.vacation, other.vacation),
 			bonus: f(self.bonus, other.bonus),
+			payments: self.payments,
 		}
 	}
 }
 
 impl<T: Copy + Mul<f64, Output = T> + Add<T, Output = T>> YearlyPlan<T> {
 	pub fn yearly_total(&self) -> QuantityPerTime<T, Yearly> {
-		QuantityPerTime::new(self.regular * 11.0 + self.vacation + self.bonus * 2.0, Yearly)
+		let PaymentCounts { regular, vacation, bonus } = self.payments;
+		QuantityPerTime::new(self.regular * regular + self.vacation * vacation + self.bonus * bonus, Yearly)
 	}
 }
 
@@ -84,10 +138,26 @@ mod tests {
 
 	#[test]
 	fn yearly_plan_yearly_total() {
-		assert!(
-			YearlyPlan { regular: Money::from(1000.0), vacation: 20.0.into(), bonus: 3.0.into() }
-				.yearly_total() == MoneyRate::new(11026.0, Yearly)
-		)
+		let plan = YearlyPlan {
+			regular: Money::from(1000.0),
+			vacation: 20.0.into(),
+			bonus: 3.0.into(),
+			payments: PaymentCounts::FULL_YEAR,
+		};
+		assert!(plan.yearly_total() == MoneyRate::new(11026.0, Yearly))
+	}
+
+	#[test]
+	fn yearly_plan_partial_year() {
+		let payments = PaymentCounts { regular: 5.5, vacation: 0.5, bonus: 1.0 };
+		let plan = YearlyPlan {
+			regular: Money::from(1000.0),
+			vacation: 1000.0.into(),
+			bonus: 1000.0.into(),
+			payments,
+		};
+		assert!(plan.yearly_total() == MoneyRate::new(7000.0, Yearly));
+		assert!(payments.yearly(MoneyRate::new(100.0, Monthly::M11)) == MoneyRate::new(550.0, Yearly));
 	}
 
 	#[test]